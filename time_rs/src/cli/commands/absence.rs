@@ -0,0 +1,388 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{collections::BTreeSet, sync::Arc};
+
+use clap::{Args, Subcommand};
+use jiff::{civil::Date, tz::TimeZone, Timestamp};
+use prodash::tree::Root;
+
+use crate::{
+    cli::Cli,
+    config::{Config, Holiday, Work},
+    data::{Absence as Record, AbsenceKind, Change, DateRange, Error as DataError, Store},
+};
+
+use super::{Command, Error, Result};
+
+#[derive(Debug, PartialEq, Eq, Args, Clone)]
+pub struct Absence {
+    #[command(subcommand)]
+    command: AbsenceCommand,
+}
+
+#[derive(Debug, PartialEq, Eq, Subcommand, Clone)]
+pub enum AbsenceCommand {
+    /// Record days off
+    Add {
+        /// Kind of the absence
+        kind: AbsenceKind,
+        /// Days off, either a single day or `FROM..TO`, both inclusive
+        range: DateRange,
+        /// Free form note
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// List the recorded absences and public holidays
+    List {
+        /// Only show the given year, defaults to the current one
+        #[arg(long)]
+        year: Option<i16>,
+    },
+    /// Remove a recorded absence
+    Remove {
+        /// Id of the absence as shown by `absence list`
+        id: String,
+    },
+    /// Show the remaining vacation allowance
+    Allowance {
+        /// Year to report, defaults to the current one
+        #[arg(long)]
+        year: Option<i16>,
+    },
+}
+
+impl Command for Absence {
+    async fn run(&self, _progress: Arc<Root>, _args: &Cli, config: Config) -> Result<()> {
+        match self.command {
+            AbsenceCommand::Add {
+                kind,
+                range,
+                ref note,
+            } => self.add(config, kind, range, note.clone()).await,
+            AbsenceCommand::List { year } => self.list(config, year).await,
+            AbsenceCommand::Remove { ref id } => self.remove(config, id).await,
+            AbsenceCommand::Allowance { year } => self.allowance(config, year).await,
+        }
+    }
+}
+
+fn current_year() -> i16 {
+    Timestamp::now().to_zoned(TimeZone::system()).year()
+}
+
+impl Absence {
+    /// Whether the subcommand changes the recorded absences.
+    pub fn mutates(&self) -> bool {
+        matches!(
+            self.command,
+            AbsenceCommand::Add { .. } | AbsenceCommand::Remove { .. }
+        )
+    }
+
+    async fn add(
+        &self,
+        config: Config,
+        kind: AbsenceKind,
+        range: DateRange,
+        note: Option<String>,
+    ) -> Result<()> {
+        let store = Store::open(&config)?;
+        let absence = Record::new(kind, range, note);
+
+        for existing in store.absences().await? {
+            if existing.id == absence.id {
+                return Err(DataError::Exists(absence.id).into());
+            }
+            if existing.from <= absence.to && absence.from <= existing.to {
+                return Err(Error::OverlappingAbsence(existing.id));
+            }
+        }
+
+        store
+            .commit(
+                format!("absence {}", absence.id),
+                vec![Change::record(absence.path(), &absence)?],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list(&self, config: Config, year: Option<i16>) -> Result<()> {
+        let year = year.unwrap_or_else(current_year);
+        let store = Store::open(&config)?;
+
+        for absence in store.absences().await? {
+            if absence.from.year() <= year && year <= absence.to.year() {
+                println!(
+                    "{:<24} {:<10} {}..{} {}",
+                    absence.id,
+                    absence.kind,
+                    absence.from,
+                    absence.to,
+                    absence.note.unwrap_or_default()
+                );
+            }
+        }
+
+        for holiday in config.work.holidays().await? {
+            if holiday.date.year() == year {
+                println!("{:<24} {:<10} {}", holiday.date, "holiday", holiday.name);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove(&self, config: Config, id: &str) -> Result<()> {
+        let store = Store::open(&config)?;
+
+        let absence = store
+            .absences()
+            .await?
+            .into_iter()
+            .find(|a| a.id == id)
+            .ok_or_else(|| DataError::NotFound(id.to_owned()))?;
+
+        store
+            .commit(
+                format!("remove absence {}", absence.id),
+                vec![Change::Remove(absence.path())],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn allowance(&self, config: Config, year: Option<i16>) -> Result<()> {
+        let year = year.unwrap_or_else(current_year);
+        let allowance = config
+            .work
+            .vacation_days
+            .ok_or(Error::MissingConfig("work.vacation_days"))?;
+        let store = Store::open(&config)?;
+        let absences = store.absences().await?;
+        let holidays = config.work.holidays().await?;
+
+        let taken = vacation_days(&config.work, &absences, &holidays, year);
+
+        println!(
+            "{year}: {allowance} days, {taken} taken, {} remaining",
+            allowance - taken as f64
+        );
+
+        Ok(())
+    }
+}
+
+/// Vacation days booked in `year`, only days that have a working time target
+/// and are no public holiday count. Days booked twice, e.g. by absences
+/// merged from another clone, count once.
+pub(crate) fn vacation_days(
+    work: &Work,
+    absences: &[Record],
+    holidays: &[Holiday],
+    year: i16,
+) -> usize {
+    absences
+        .iter()
+        .filter(|a| a.kind == AbsenceKind::Vacation)
+        .flat_map(Record::days)
+        .filter(|d: &Date| d.year() == year)
+        .filter(|d| !work.target(*d).is_zero())
+        .filter(|d| !holidays.iter().any(|h| h.date == *d))
+        .collect::<BTreeSet<_>>()
+        .len()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use figment::Figment;
+    use jiff::civil::date;
+    use prodash::tree::root::Options;
+    use rstest::*;
+
+    use super::*;
+    use crate::data::tests::init_store;
+
+    #[fixture]
+    fn progress() -> Arc<Root> {
+        Options::default().create().into()
+    }
+
+    #[rstest]
+    fn vacation_days_skip_weekends_holidays_and_other_years() {
+        let work = Work {
+            weekly_hours: Some(40.0),
+            ..Default::default()
+        };
+        let absences = vec![
+            Record::new(
+                AbsenceKind::Vacation,
+                "2026-12-24..2027-01-04".parse().unwrap(),
+                None,
+            ),
+            Record::new(AbsenceKind::Sick, "2026-12-01".parse().unwrap(), None),
+        ];
+        let holidays = vec![Holiday {
+            date: date(2026, 12, 25),
+            name: "Christmas Day".to_string(),
+        }];
+
+        // 24., 28., 29., 30., 31.
+        assert_eq!(5, vacation_days(&work, &absences, &holidays, 2026));
+        // 1., 4.
+        assert_eq!(2, vacation_days(&work, &absences, &holidays, 2027));
+    }
+
+    #[rstest]
+    fn overlapping_vacation_days_count_once() {
+        let work = Work {
+            weekly_hours: Some(40.0),
+            ..Default::default()
+        };
+        let absences = ["2026-12-21..2026-12-24", "2026-12-23..2026-12-25"]
+            .map(|range| Record::new(AbsenceKind::Vacation, range.parse().unwrap(), None));
+
+        // 21. to 25.
+        assert_eq!(5, vacation_days(&work, &absences, &[], 2026));
+    }
+
+    #[rstest]
+    #[case::inside("2026-12-27..2026-12-30")]
+    #[case::start("2026-12-20..2026-12-24")]
+    #[case::end("2026-12-31..2027-01-02")]
+    #[case::around("2026-12-20..2027-01-02")]
+    #[tokio::test]
+    async fn overlapping_absences_are_rejected(
+        progress: Arc<Root>,
+        #[case] range: &str,
+    ) -> eyre::Result<()> {
+        let tmp = assert_fs::TempDir::new()?;
+        let store = init_store(&tmp);
+        let figment = Figment::new().merge(("data_dir", tmp.path()));
+        let add = |kind, range: &str| Absence {
+            command: AbsenceCommand::Add {
+                kind,
+                range: range.parse().unwrap(),
+                note: None,
+            },
+        };
+
+        add(AbsenceKind::Vacation, "2026-12-24..2026-12-31")
+            .run(
+                progress.clone(),
+                &Cli::default(),
+                figment.clone().try_into()?,
+            )
+            .await?;
+        let existing = store.absences().await?.remove(0).id;
+        let result = add(AbsenceKind::Sick, range)
+            .run(
+                progress.clone(),
+                &Cli::default(),
+                figment.clone().try_into()?,
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::OverlappingAbsence(id)) if id == existing));
+        assert_eq!(1, store.absences().await?.len());
+
+        add(AbsenceKind::Sick, "2027-01-01")
+            .run(progress, &Cli::default(), figment.try_into()?)
+            .await?;
+        assert_eq!(2, store.absences().await?.len());
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn add_and_remove_absences(progress: Arc<Root>) -> eyre::Result<()> {
+        let tmp = assert_fs::TempDir::new()?;
+        let store = init_store(&tmp);
+        let figment = Figment::new().merge(("data_dir", tmp.path()));
+        let add = Absence {
+            command: AbsenceCommand::Add {
+                kind: AbsenceKind::Vacation,
+                range: "2026-12-24..2026-12-31".parse()?,
+                note: Some("winter break".to_string()),
+            },
+        };
+
+        add.run(
+            progress.clone(),
+            &Cli::default(),
+            figment.clone().try_into()?,
+        )
+        .await?;
+
+        let absences = store.absences().await?;
+        assert_eq!(1, absences.len());
+        assert_eq!(date(2026, 12, 31), absences[0].to);
+
+        let again = add
+            .run(
+                progress.clone(),
+                &Cli::default(),
+                figment.clone().try_into()?,
+            )
+            .await;
+        assert!(matches!(again, Err(Error::Data(DataError::Exists(_)))));
+
+        let remove = Absence {
+            command: AbsenceCommand::Remove {
+                id: absences[0].id.clone(),
+            },
+        };
+        remove
+            .run(progress, &Cli::default(), figment.try_into()?)
+            .await?;
+
+        assert!(store.absences().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn remove_unknown_absence_fails(progress: Arc<Root>) -> eyre::Result<()> {
+        let tmp = assert_fs::TempDir::new()?;
+        init_store(&tmp);
+        let config = Figment::new().merge(("data_dir", tmp.path())).try_into()?;
+        let remove = Absence {
+            command: AbsenceCommand::Remove {
+                id: "unknown".to_string(),
+            },
+        };
+
+        let result = remove.run(progress, &Cli::default(), config).await;
+
+        assert!(matches!(result, Err(Error::Data(DataError::NotFound(_)))));
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn allowance_requires_configuration(progress: Arc<Root>) -> eyre::Result<()> {
+        let tmp = assert_fs::TempDir::new()?;
+        let config = Figment::new().merge(("data_dir", tmp.path())).try_into()?;
+        let allowance = Absence {
+            command: AbsenceCommand::Allowance { year: Some(2026) },
+        };
+
+        let result = allowance.run(progress, &Cli::default(), config).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::MissingConfig("work.vacation_days"))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,358 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{fmt::Write, sync::Arc};
+
+use clap::Args;
+use jiff::{civil::Date, tz::TimeZone, SignedDuration, Timestamp, ToSpan};
+use prodash::tree::Root;
+
+use crate::{
+    cli::Cli,
+    config::{work::hours, Config, Holiday, Work},
+    data::{Absence, Entry, Store},
+};
+
+use super::{format_duration, Command, Error, Result};
+
+#[derive(Debug, PartialEq, Eq, Args)]
+pub struct Balance {
+    /// First day to show, the balance is still accumulated from `work.balance_start`
+    #[arg(long)]
+    pub from: Option<Date>,
+
+    /// Last day to show, defaults to today
+    #[arg(long)]
+    pub to: Option<Date>,
+}
+
+impl Command for Balance {
+    async fn run(&self, _progress: Arc<Root>, _args: &Cli, config: Config) -> Result<()> {
+        let start = config
+            .work
+            .balance_start
+            .ok_or(Error::MissingConfig("work.balance_start"))?;
+        let store = Store::open(&config)?;
+        let entries = store.entries().await?;
+        let absences = store.absences().await?;
+        let holidays = config.work.holidays().await?;
+
+        let now = Timestamp::now();
+        let tz = TimeZone::system();
+        let to = self.to.unwrap_or_else(|| now.to_zoned(tz.clone()).date());
+
+        let days = days(
+            &config.work,
+            &entries,
+            &absences,
+            &holidays,
+            start,
+            to,
+            &tz,
+            now,
+        );
+        print!(
+            "{}",
+            render(&days, hours(config.work.initial_balance), self.from)
+        );
+
+        Ok(())
+    }
+}
+
+/// Target and actual working time of a single day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Day {
+    pub date: Date,
+    pub target: SignedDuration,
+    pub actual: SignedDuration,
+    /// Why there is no target for this day.
+    pub off: Option<String>,
+}
+
+impl Day {
+    pub fn diff(&self) -> SignedDuration {
+        self.actual - self.target
+    }
+}
+
+/// Calculates target and actual time for every day from `from` to `to`,
+/// days with an absence or public holiday have no target.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn days(
+    work: &Work,
+    entries: &[Entry],
+    absences: &[Absence],
+    holidays: &[Holiday],
+    from: Date,
+    to: Date,
+    tz: &TimeZone,
+    now: Timestamp,
+) -> Vec<Day> {
+    from.series(1.day())
+        .take_while(|date| *date <= to)
+        .map(|date| {
+            let off = holidays
+                .iter()
+                .find(|h| h.date == date)
+                .map(|h| h.name.clone())
+                .or_else(|| {
+                    absences
+                        .iter()
+                        .find(|a| a.contains(date))
+                        .map(|a| a.kind.to_string())
+                });
+            let target = match off {
+                Some(_) => SignedDuration::ZERO,
+                None => work.target(date),
+            };
+            let actual = entries.iter().map(|e| e.duration_on(date, tz, now)).sum();
+
+            Day {
+                date,
+                target,
+                actual,
+                off,
+            }
+        })
+        .collect()
+}
+
+/// Renders one line per day and a summary per ISO week, days before `from`
+/// only contribute to the running balance.
+pub(crate) fn render(days: &[Day], initial: SignedDuration, from: Option<Date>) -> String {
+    let mut out = String::new();
+    let mut balance = initial;
+    let mut week: Option<(Date, SignedDuration, SignedDuration)> = None;
+
+    let _ = writeln!(
+        out,
+        "{:<13} {:>8} {:>8} {:>8} {:>9}",
+        "date", "target", "actual", "diff", "balance"
+    );
+
+    for (idx, day) in days.iter().enumerate() {
+        balance += day.diff();
+
+        if from.is_some_and(|from| day.date < from) {
+            continue;
+        }
+
+        let (first, target, actual) =
+            week.get_or_insert((day.date, SignedDuration::ZERO, SignedDuration::ZERO));
+        let first = *first;
+        *target += day.target;
+        *actual += day.actual;
+
+        let line = format!(
+            "{:<13} {:>8} {:>8} {:>8} {:>9} {}",
+            day.date,
+            format_duration(day.target, false),
+            format_duration(day.actual, false),
+            format_duration(day.diff(), true),
+            format_duration(balance, true),
+            day.off.as_deref().unwrap_or_default(),
+        );
+        let _ = writeln!(out, "{}", line.trim_end());
+
+        let last_of_week = days
+            .get(idx + 1)
+            .is_none_or(|next| next.date.iso_week_date().week() != day.date.iso_week_date().week());
+        if last_of_week {
+            if let Some((_, target, actual)) = week.take() {
+                let iso = first.iso_week_date();
+                let _ = writeln!(
+                    out,
+                    "{:<13} {:>8} {:>8} {:>8} {:>9}",
+                    format!("{}-W{:02}", iso.year(), iso.week()),
+                    format_duration(target, false),
+                    format_duration(actual, false),
+                    format_duration(actual - target, true),
+                    format_duration(balance, true),
+                );
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use assert_fs::prelude::*;
+    use figment::Figment;
+    use jiff::civil::date;
+    use prodash::tree::root::Options;
+    use rstest::*;
+
+    use super::*;
+    use crate::data::AbsenceKind;
+
+    #[fixture]
+    fn work() -> Work {
+        Work {
+            weekly_hours: Some(40.0),
+            balance_start: Some(date(2026, 10, 19)),
+            ..Default::default()
+        }
+    }
+
+    fn entry(start: &str, end: &str) -> Entry {
+        Entry {
+            end: Some(end.parse().unwrap()),
+            ..Entry::new(None, vec![], start.parse().unwrap())
+        }
+    }
+
+    #[rstest]
+    fn days_compare_target_and_actual(work: Work) {
+        let entries = vec![
+            entry("2026-10-19T08:00:00Z", "2026-10-19T12:00:00Z"),
+            entry("2026-10-19T13:00:00Z", "2026-10-19T18:00:00Z"),
+        ];
+        let now = "2026-10-22T00:00:00Z".parse().unwrap();
+
+        let days = days(
+            &work,
+            &entries,
+            &[],
+            &[],
+            date(2026, 10, 19),
+            date(2026, 10, 20),
+            &TimeZone::UTC,
+            now,
+        );
+
+        assert_eq!(2, days.len());
+        assert_eq!(SignedDuration::from_hours(9), days[0].actual);
+        assert_eq!(SignedDuration::from_hours(1), days[0].diff());
+        assert_eq!(SignedDuration::from_hours(-8), days[1].diff());
+    }
+
+    #[rstest]
+    fn absences_clear_the_target(work: Work) {
+        let absences = vec![Absence {
+            id: "vacation".to_string(),
+            kind: AbsenceKind::Vacation,
+            from: date(2026, 10, 20),
+            to: date(2026, 10, 21),
+            note: None,
+        }];
+        let now = "2026-10-22T00:00:00Z".parse().unwrap();
+
+        let days = days(
+            &work,
+            &[],
+            &absences,
+            &[],
+            date(2026, 10, 19),
+            date(2026, 10, 22),
+            &TimeZone::UTC,
+            now,
+        );
+
+        let targets = days.iter().map(|d| d.target.as_hours()).collect::<Vec<_>>();
+        assert_eq!(vec![8, 0, 0, 8], targets);
+        assert_eq!(Some("vacation".to_string()), days[1].off);
+    }
+
+    #[rstest]
+    fn holidays_clear_the_target(work: Work) {
+        let holidays = vec![Holiday {
+            date: date(2026, 10, 19),
+            name: "Founders Day".to_string(),
+        }];
+        let now = "2026-10-22T00:00:00Z".parse().unwrap();
+
+        let days = days(
+            &work,
+            &[],
+            &[],
+            &holidays,
+            date(2026, 10, 19),
+            date(2026, 10, 20),
+            &TimeZone::UTC,
+            now,
+        );
+
+        assert_eq!(SignedDuration::ZERO, days[0].target);
+        assert_eq!(Some("Founders Day".to_string()), days[0].off);
+        assert_eq!(SignedDuration::from_hours(8), days[1].target);
+    }
+
+    #[rstest]
+    fn render_accumulates_balance_and_weeks(work: Work) {
+        let entries = vec![entry("2026-10-23T08:00:00Z", "2026-10-23T18:00:00Z")];
+        let now = "2026-10-27T00:00:00Z".parse().unwrap();
+        let days = days(
+            &work,
+            &entries,
+            &[],
+            &[],
+            date(2026, 10, 23),
+            date(2026, 10, 26),
+            &TimeZone::UTC,
+            now,
+        );
+
+        let out = render(
+            &days,
+            SignedDuration::from_hours(1),
+            Some(date(2026, 10, 24)),
+        );
+
+        let lines = out.lines().collect::<Vec<_>>();
+        assert_eq!(6, lines.len(), "{out}");
+        assert!(lines[1].starts_with("2026-10-24"));
+        assert!(lines[1].ends_with("+3:00"), "{out}");
+        assert!(lines[3].starts_with("2026-W43"), "{out}");
+        assert!(lines[4].starts_with("2026-10-26"));
+        assert!(lines[4].ends_with("-5:00"), "{out}");
+        assert!(lines[5].starts_with("2026-W44"), "{out}");
+    }
+
+    #[tokio::test]
+    async fn fails_without_balance_start() -> eyre::Result<()> {
+        let tmp = assert_fs::TempDir::new()?;
+        let config: Config = Figment::new().merge(("data_dir", tmp.path())).try_into()?;
+        let progress: Arc<_> = Options::default().create().into();
+        let balance = Balance {
+            from: None,
+            to: None,
+        };
+
+        let result = balance.run(progress, &Cli::default(), config).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::MissingConfig("work.balance_start"))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn runs_with_balance_start() -> eyre::Result<()> {
+        let tmp = assert_fs::TempDir::new()?;
+        tmp.child("repo").create_dir_all()?;
+        let config: Config = Figment::new()
+            .merge(("data_dir", tmp.path()))
+            .merge(("work.balance_start", "2026-10-19"))
+            .merge(("work.weekly_hours", 40))
+            .try_into()?;
+        let progress: Arc<_> = Options::default().create().into();
+        let balance = Balance {
+            from: None,
+            to: Some(date(2026, 10, 25)),
+        };
+
+        let result = balance.run(progress, &Cli::default(), config).await;
+
+        assert!(result.is_ok());
+
+        Ok(())
+    }
+}
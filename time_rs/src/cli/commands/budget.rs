@@ -0,0 +1,404 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{collections::BTreeMap, fmt, fmt::Write, sync::Arc};
+
+use clap::Args;
+use jiff::{SignedDuration, Timestamp, ToSpan, Zoned};
+use prodash::tree::Root;
+
+use crate::{
+    cli::Cli,
+    config::{work::hours, Budget as Limits, Config},
+    data::{Entry, Store},
+};
+
+use super::{format_duration, Command, Result};
+
+/// Width of the bars in the burn-down chart.
+const CHART_WIDTH: usize = 40;
+
+#[derive(Debug, PartialEq, Eq, Args)]
+pub struct Budget {
+    /// Only show the budget of this project
+    pub project: Option<String>,
+}
+
+impl Command for Budget {
+    async fn run(&self, _progress: Arc<Root>, _args: &Cli, config: Config) -> Result<()> {
+        let store = Store::open(&config)?;
+        let entries = store.entries().await?;
+        let limits = limits(&config, &store).await?;
+
+        let now = Zoned::now();
+        let usages = usages(&limits, &entries, &now)
+            .into_iter()
+            .filter(|u| self.project.as_ref().is_none_or(|p| *p == u.project))
+            .collect::<Vec<_>>();
+
+        print!("{}", render(&usages, &entries, &now));
+
+        Ok(())
+    }
+}
+
+/// Budgets shared through the repository, each field overridden by the
+/// configured one if that is set.
+pub(crate) async fn limits(config: &Config, store: &Store) -> Result<BTreeMap<String, Limits>> {
+    let mut limits = store.budgets().await?;
+
+    for (name, project) in config.projects.iter().filter(|(_, p)| !p.budget.is_empty()) {
+        let limit = limits.entry(name.clone()).or_default();
+        limit.total = project.budget.total.or(limit.total);
+        limit.monthly = project.budget.monthly.or(limit.monthly);
+    }
+
+    Ok(limits)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Period {
+    Total,
+    Monthly,
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Period::Total => f.write_str("total"),
+            Period::Monthly => f.write_str("monthly"),
+        }
+    }
+}
+
+/// How much of a project budget has been used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Usage {
+    pub project: String,
+    pub period: Period,
+    pub budget: SignedDuration,
+    pub used: SignedDuration,
+}
+
+impl Usage {
+    pub fn ratio(&self) -> f64 {
+        if self.budget.is_zero() {
+            return f64::INFINITY;
+        }
+
+        self.used.as_secs_f64() / self.budget.as_secs_f64()
+    }
+
+    pub fn remaining(&self) -> SignedDuration {
+        self.budget - self.used
+    }
+
+    /// A warning once 80% of the budget are used.
+    pub fn warning(&self) -> Option<String> {
+        let level = match self.ratio() {
+            r if r >= 1.0 => "exceeded",
+            r if r >= 0.8 => "at 80%",
+            _ => return None,
+        };
+
+        Some(format!(
+            "{} budget of {} {level}: {} of {} used",
+            self.period,
+            self.project,
+            format_duration(self.used, false),
+            format_duration(self.budget, false)
+        ))
+    }
+}
+
+fn month_start(now: &Zoned) -> Timestamp {
+    now.first_of_month()
+        .and_then(|d| d.start_of_day())
+        .map(|d| d.timestamp())
+        .unwrap_or_else(|_| now.timestamp())
+}
+
+fn used(entries: &[Entry], project: &str, since: Timestamp, until: Timestamp) -> SignedDuration {
+    entries
+        .iter()
+        .filter(|e| e.project.as_deref() == Some(project))
+        .filter(|e| e.start >= since && e.start < until)
+        .map(|e| e.end.unwrap_or(until).min(until).duration_since(e.start))
+        .filter(|d| d.is_positive())
+        .sum()
+}
+
+/// The usage of every configured budget, monthly budgets count the month of `now`.
+pub(crate) fn usages(
+    limits: &BTreeMap<String, Limits>,
+    entries: &[Entry],
+    now: &Zoned,
+) -> Vec<Usage> {
+    let until = now.timestamp();
+
+    limits
+        .iter()
+        .flat_map(|(project, limits)| {
+            let total = limits.total.map(|budget| Usage {
+                project: project.clone(),
+                period: Period::Total,
+                budget: hours(budget),
+                used: used(entries, project, Timestamp::MIN, until),
+            });
+            let monthly = limits.monthly.map(|budget| Usage {
+                project: project.clone(),
+                period: Period::Monthly,
+                budget: hours(budget),
+                used: used(entries, project, month_start(now), until),
+            });
+
+            total.into_iter().chain(monthly)
+        })
+        .collect()
+}
+
+/// Remaining budget at the end of every month for total budgets, and of every
+/// day of the current month for monthly budgets.
+pub(crate) fn burn_down(
+    usage: &Usage,
+    entries: &[Entry],
+    now: &Zoned,
+) -> Vec<(String, SignedDuration)> {
+    let tz = now.time_zone().clone();
+    let (since, step, format) = match usage.period {
+        Period::Total => {
+            let first = entries
+                .iter()
+                .filter(|e| e.project.as_deref() == Some(usage.project.as_str()))
+                .map(|e| e.start.to_zoned(tz.clone()))
+                .next()
+                .unwrap_or_else(|| now.clone());
+            (month_start(&first), 1.month(), "%Y-%m")
+        }
+        Period::Monthly => (month_start(now), 1.day(), "%Y-%m-%d"),
+    };
+
+    let Ok(first) = since.to_zoned(tz).start_of_day() else {
+        return vec![];
+    };
+
+    first
+        .datetime()
+        .series(step)
+        .filter_map(|dt| dt.to_zoned(now.time_zone().clone()).ok())
+        .take_while(|start| start <= now)
+        .map(|start| {
+            let end = start
+                .checked_add(step)
+                .map(|e| e.timestamp().min(now.timestamp()))
+                .unwrap_or_else(|_| now.timestamp());
+            let used = used(entries, &usage.project, since, end);
+
+            (start.strftime(format).to_string(), usage.budget - used)
+        })
+        .collect()
+}
+
+fn bar(remaining: SignedDuration, budget: SignedDuration) -> String {
+    let ratio = match budget.is_zero() {
+        true => 0.0,
+        false => remaining.as_secs_f64() / budget.as_secs_f64(),
+    };
+    let width = (ratio.clamp(0.0, 1.0) * CHART_WIDTH as f64).round() as usize;
+
+    "#".repeat(width)
+}
+
+pub(crate) fn render(usages: &[Usage], entries: &[Entry], now: &Zoned) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(
+        out,
+        "{:<20} {:<8} {:>8} {:>8} {:>9} {:>5}",
+        "project", "budget", "limit", "used", "remaining", "used"
+    );
+    for usage in usages {
+        let _ = writeln!(
+            out,
+            "{:<20} {:<8} {:>8} {:>8} {:>9} {:>4.0}%",
+            usage.project,
+            usage.period,
+            format_duration(usage.budget, false),
+            format_duration(usage.used, false),
+            format_duration(usage.remaining(), true),
+            usage.ratio() * 100.0,
+        );
+    }
+
+    for usage in usages {
+        let _ = writeln!(out, "\n{} ({})", usage.project, usage.period);
+        for (label, remaining) in burn_down(usage, entries, now) {
+            let _ = writeln!(
+                out,
+                "{label:<10} {:<width$} {}",
+                bar(remaining, usage.budget),
+                format_duration(remaining, true),
+                width = CHART_WIDTH
+            );
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use figment::Figment;
+    use jiff::tz::TimeZone;
+    use prodash::tree::root::Options;
+    use rstest::*;
+
+    use super::*;
+    use crate::data::tests::init_store;
+
+    fn entry(project: &str, start: &str, end: &str) -> Entry {
+        Entry {
+            end: Some(end.parse().unwrap()),
+            ..Entry::new(Some(project.to_string()), vec![], start.parse().unwrap())
+        }
+    }
+
+    #[fixture]
+    fn entries() -> Vec<Entry> {
+        vec![
+            entry("time_rs", "2026-09-10T08:00:00Z", "2026-09-10T16:00:00Z"),
+            entry("time_rs", "2026-10-01T08:00:00Z", "2026-10-01T12:00:00Z"),
+            entry("other", "2026-10-02T08:00:00Z", "2026-10-02T12:00:00Z"),
+        ]
+    }
+
+    #[fixture]
+    fn now() -> Zoned {
+        "2026-10-19T12:00:00Z"
+            .parse::<Timestamp>()
+            .unwrap()
+            .to_zoned(TimeZone::UTC)
+    }
+
+    #[rstest]
+    fn usages_split_total_and_monthly(entries: Vec<Entry>, now: Zoned) {
+        let limits = BTreeMap::from([(
+            "time_rs".to_string(),
+            Limits {
+                total: Some(15.0),
+                monthly: Some(4.0),
+            },
+        )]);
+
+        let usages = usages(&limits, &entries, &now);
+
+        assert_eq!(2, usages.len());
+        assert_eq!(Period::Total, usages[0].period);
+        assert_eq!(SignedDuration::from_hours(12), usages[0].used);
+        assert_eq!(SignedDuration::from_hours(3), usages[0].remaining());
+        assert_eq!(Period::Monthly, usages[1].period);
+        assert_eq!(SignedDuration::from_hours(4), usages[1].used);
+    }
+
+    #[rstest]
+    #[case(7.0, None)]
+    #[case(8.0, Some("at 80%"))]
+    #[case(10.0, Some("exceeded"))]
+    fn warnings_start_at_eighty_percent(#[case] used: f64, #[case] level: Option<&str>) {
+        let usage = Usage {
+            project: "time_rs".to_string(),
+            period: Period::Total,
+            budget: hours(10.0),
+            used: hours(used),
+        };
+
+        let warning = usage.warning();
+
+        match level {
+            Some(level) => assert!(warning.unwrap().contains(level)),
+            None => assert_eq!(None, warning),
+        }
+    }
+
+    #[rstest]
+    fn burn_down_per_month(entries: Vec<Entry>, now: Zoned) {
+        let usage = Usage {
+            project: "time_rs".to_string(),
+            period: Period::Total,
+            budget: hours(20.0),
+            used: hours(12.0),
+        };
+
+        let points = burn_down(&usage, &entries, &now);
+
+        assert_eq!(
+            vec![
+                ("2026-09".to_string(), hours(12.0)),
+                ("2026-10".to_string(), hours(8.0)),
+            ],
+            points
+        );
+    }
+
+    #[rstest]
+    fn burn_down_per_day(entries: Vec<Entry>, now: Zoned) {
+        let usage = Usage {
+            project: "time_rs".to_string(),
+            period: Period::Monthly,
+            budget: hours(10.0),
+            used: hours(4.0),
+        };
+
+        let points = burn_down(&usage, &entries, &now);
+
+        assert_eq!(19, points.len());
+        assert_eq!(("2026-10-01".to_string(), hours(6.0)), points[0]);
+        assert_eq!(("2026-10-19".to_string(), hours(6.0)), points[18]);
+    }
+
+    #[test]
+    fn bars_are_clamped() {
+        assert_eq!(CHART_WIDTH, bar(hours(20.0), hours(10.0)).len());
+        assert_eq!(CHART_WIDTH / 2, bar(hours(5.0), hours(10.0)).len());
+        assert_eq!(0, bar(hours(-5.0), hours(10.0)).len());
+    }
+
+    #[tokio::test]
+    async fn configured_budgets_override_the_repository() -> eyre::Result<()> {
+        let tmp = assert_fs::TempDir::new()?;
+        let store = init_store(&tmp);
+        store
+            .commit(
+                "budgets",
+                vec![crate::data::Change::Write(
+                    crate::data::BUDGETS_FILE.into(),
+                    "[time_rs]\ntotal = 10\nmonthly = 2\n[other]\nmonthly = 5\n".to_string(),
+                )],
+            )
+            .await?;
+        let config: Config = Figment::new()
+            .merge(("data_dir", tmp.path()))
+            .merge(("projects.time_rs.budget.total", 20))
+            .merge(("projects.new.budget.monthly", 1))
+            .try_into()?;
+
+        let limits = limits(&config, &store).await?;
+
+        assert_eq!(Some(20.0), limits["time_rs"].total);
+        assert_eq!(Some(2.0), limits["time_rs"].monthly);
+        assert_eq!(Some(5.0), limits["other"].monthly);
+        assert_eq!(None, limits["new"].total);
+        assert_eq!(Some(1.0), limits["new"].monthly);
+
+        let progress: Arc<_> = Options::default().create().into();
+        let result = Budget { project: None }
+            .run(progress, &Cli::default(), config)
+            .await;
+        assert!(result.is_ok());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,357 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use clap::Args;
+use jiff::{civil::Date, tz::TimeZone, SignedDuration, Timestamp};
+use prodash::tree::Root;
+use serde::Serialize;
+
+use crate::{
+    cli::Cli,
+    config::{compliance::RuleSet, work::hours, Config},
+    data::{Entry, Store},
+};
+
+use super::{format_duration, Command, Error, OutputFormat, Result};
+
+#[derive(Debug, PartialEq, Eq, Args)]
+pub struct Check {
+    /// First day to check
+    #[arg(long)]
+    pub from: Option<Date>,
+
+    /// Last day to check
+    #[arg(long)]
+    pub to: Option<Date>,
+
+    /// Output format of the report
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+impl Command for Check {
+    async fn run(&self, _progress: Arc<Root>, _args: &Cli, config: Config) -> Result<()> {
+        let store = Store::open(&config)?;
+        let entries = store.entries().await?;
+
+        let violations = evaluate(
+            &config.compliance.rules,
+            &entries,
+            self.from,
+            self.to,
+            &TimeZone::system(),
+            Timestamp::now(),
+        );
+
+        match self.format {
+            OutputFormat::Text => violations.iter().for_each(|v| println!("{v}")),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&violations)?),
+        }
+
+        match violations.len() {
+            0 => Ok(()),
+            n => Err(Error::Violations(n)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Rule {
+    MaxDailyHours,
+    MinRestHours,
+    Breaks,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = match self {
+            Rule::MaxDailyHours => "max_daily_hours",
+            Rule::MinRestHours => "min_rest_hours",
+            Rule::Breaks => "breaks",
+        };
+
+        f.write_str(rule)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Violation {
+    pub date: Date,
+    pub rule_set: String,
+    pub rule: Rule,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}/{}: {}",
+            self.date, self.rule_set, self.rule, self.message
+        )
+    }
+}
+
+/// Checks `entries` against every rule set, entries belong to the day they
+/// were started on.
+pub(crate) fn evaluate(
+    rules: &BTreeMap<String, RuleSet>,
+    entries: &[Entry],
+    from: Option<Date>,
+    to: Option<Date>,
+    tz: &TimeZone,
+    now: Timestamp,
+) -> Vec<Violation> {
+    let mut days: BTreeMap<Date, Vec<&Entry>> = BTreeMap::new();
+    for entry in entries {
+        let date = entry.start.to_zoned(tz.clone()).date();
+        days.entry(date).or_default().push(entry);
+    }
+    days.values_mut().for_each(|d| d.sort_by_key(|e| e.start));
+
+    let mut violations = vec![];
+    let mut previous: Option<&Vec<&Entry>> = None;
+
+    for (date, day) in &days {
+        let in_range = from.is_none_or(|from| *date >= from) && to.is_none_or(|to| *date <= to);
+
+        if in_range {
+            for (name, rule_set) in rules {
+                let mut violation = |rule, message| {
+                    violations.push(Violation {
+                        date: *date,
+                        rule_set: name.clone(),
+                        rule,
+                        message,
+                    })
+                };
+
+                check_day(rule_set, day, previous, now, &mut violation);
+            }
+        }
+
+        previous = Some(day);
+    }
+
+    violations
+}
+
+fn check_day<F>(
+    rules: &RuleSet,
+    day: &[&Entry],
+    previous: Option<&Vec<&Entry>>,
+    now: Timestamp,
+    violation: &mut F,
+) where
+    F: FnMut(Rule, String),
+{
+    let worked: SignedDuration = day.iter().map(|e| e.duration(now)).sum();
+    let paused: SignedDuration = day
+        .windows(2)
+        .map(|pair| pair[1].start.duration_since(pair[0].end.unwrap_or(now)))
+        .filter(|gap| gap.is_positive())
+        .sum();
+
+    if let Some(max) = rules.max_daily_hours.map(hours) {
+        if worked > max {
+            violation(
+                Rule::MaxDailyHours,
+                format!(
+                    "worked {}, at most {} allowed",
+                    format_duration(worked, false),
+                    format_duration(max, false)
+                ),
+            );
+        }
+    }
+
+    let required = rules
+        .breaks
+        .iter()
+        .filter(|b| worked > hours(b.after_hours))
+        .map(|b| SignedDuration::from_mins(b.minutes.into()))
+        .max();
+    if let Some(required) = required {
+        if paused < required {
+            violation(
+                Rule::Breaks,
+                format!(
+                    "worked {} with {} of breaks, {} required",
+                    format_duration(worked, false),
+                    format_duration(paused, false),
+                    format_duration(required, false)
+                ),
+            );
+        }
+    }
+
+    let last_end = previous.and_then(|p| p.iter().map(|e| e.end.unwrap_or(now)).max());
+    if let (Some(min), Some(last_end), Some(first)) =
+        (rules.min_rest_hours.map(hours), last_end, day.first())
+    {
+        let rest = first.start.duration_since(last_end);
+        if rest < min {
+            violation(
+                Rule::MinRestHours,
+                format!(
+                    "rested {}, at least {} required",
+                    format_duration(rest, false),
+                    format_duration(min, false)
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use figment::Figment;
+    use jiff::civil::date;
+    use prodash::tree::root::Options;
+    use rstest::*;
+
+    use super::*;
+    use crate::config::compliance::BreakRule;
+
+    #[fixture]
+    fn rules() -> BTreeMap<String, RuleSet> {
+        BTreeMap::from([(
+            "arbzg".to_string(),
+            RuleSet {
+                max_daily_hours: Some(10.0),
+                min_rest_hours: Some(11.0),
+                breaks: vec![
+                    BreakRule {
+                        after_hours: 6.0,
+                        minutes: 30,
+                    },
+                    BreakRule {
+                        after_hours: 9.0,
+                        minutes: 45,
+                    },
+                ],
+            },
+        )])
+    }
+
+    fn entry(start: &str, end: &str) -> Entry {
+        Entry {
+            end: Some(end.parse().unwrap()),
+            ..Entry::new(None, vec![], start.parse().unwrap())
+        }
+    }
+
+    fn check(rules: &BTreeMap<String, RuleSet>, entries: &[Entry]) -> Vec<(Date, Rule)> {
+        let now = "2027-01-01T00:00:00Z".parse().unwrap();
+
+        evaluate(rules, entries, None, None, &TimeZone::UTC, now)
+            .into_iter()
+            .map(|v| (v.date, v.rule))
+            .collect()
+    }
+
+    #[rstest]
+    fn compliant_days_pass(rules: BTreeMap<String, RuleSet>) {
+        let entries = vec![
+            entry("2026-10-19T08:00:00Z", "2026-10-19T12:00:00Z"),
+            entry("2026-10-19T12:30:00Z", "2026-10-19T17:00:00Z"),
+            entry("2026-10-20T08:00:00Z", "2026-10-20T12:00:00Z"),
+        ];
+
+        assert!(check(&rules, &entries).is_empty());
+    }
+
+    #[rstest]
+    #[case::long_day(
+        vec![
+            entry("2026-10-19T06:00:00Z", "2026-10-19T12:00:00Z"),
+            entry("2026-10-19T13:00:00Z", "2026-10-19T18:00:00Z"),
+        ],
+        vec![(date(2026, 10, 19), Rule::MaxDailyHours)],
+    )]
+    #[case::missing_break(
+        vec![entry("2026-10-19T08:00:00Z", "2026-10-19T15:00:00Z")],
+        vec![(date(2026, 10, 19), Rule::Breaks)],
+    )]
+    #[case::short_break_after_nine_hours(
+        vec![
+            entry("2026-10-19T07:00:00Z", "2026-10-19T12:00:00Z"),
+            entry("2026-10-19T12:30:00Z", "2026-10-19T17:00:00Z"),
+        ],
+        vec![(date(2026, 10, 19), Rule::Breaks)],
+    )]
+    #[case::short_rest(
+        vec![
+            entry("2026-10-19T16:00:00Z", "2026-10-19T21:00:00Z"),
+            entry("2026-10-20T06:00:00Z", "2026-10-20T10:00:00Z"),
+        ],
+        vec![(date(2026, 10, 20), Rule::MinRestHours)],
+    )]
+    fn violations_are_reported(
+        rules: BTreeMap<String, RuleSet>,
+        #[case] entries: Vec<Entry>,
+        #[case] expected: Vec<(Date, Rule)>,
+    ) {
+        assert_eq!(expected, check(&rules, &entries));
+    }
+
+    #[rstest]
+    fn range_limits_reported_days(rules: BTreeMap<String, RuleSet>) {
+        let entries = vec![
+            entry("2026-10-19T08:00:00Z", "2026-10-19T20:00:00Z"),
+            entry("2026-10-20T08:00:00Z", "2026-10-20T20:00:00Z"),
+        ];
+        let now = "2027-01-01T00:00:00Z".parse().unwrap();
+
+        let violations = evaluate(
+            &rules,
+            &entries,
+            Some(date(2026, 10, 20)),
+            None,
+            &TimeZone::UTC,
+            now,
+        );
+
+        assert!(violations.iter().all(|v| v.date == date(2026, 10, 20)));
+        assert!(!violations.is_empty());
+    }
+
+    #[test]
+    fn violations_serialize_to_json() {
+        let violation = Violation {
+            date: date(2026, 10, 19),
+            rule_set: "arbzg".to_string(),
+            rule: Rule::MinRestHours,
+            message: "rested 8:00, at least 11:00 required".to_string(),
+        };
+
+        let json = serde_json::to_value(&violation).unwrap();
+
+        assert_eq!("2026-10-19", json["date"]);
+        assert_eq!("min_rest_hours", json["rule"]);
+    }
+
+    #[tokio::test]
+    async fn no_rules_no_violations() -> eyre::Result<()> {
+        let tmp = assert_fs::TempDir::new()?;
+        let config: Config = Figment::new().merge(("data_dir", tmp.path())).try_into()?;
+        let progress: Arc<_> = Options::default().create().into();
+        let check = Check {
+            from: None,
+            to: None,
+            format: OutputFormat::Json,
+        };
+
+        let result = check.run(progress, &Cli::default(), config).await;
+
+        assert!(result.is_ok());
+
+        Ok(())
+    }
+}
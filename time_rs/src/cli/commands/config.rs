@@ -0,0 +1,568 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{
+    fmt::Write,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use clap::{Args, Subcommand};
+use figment::value::Value;
+use prodash::tree::Root;
+
+use crate::{
+    cli::Cli,
+    config::{edit, schema, validate, Config, Error as ConfigError, Provenance},
+};
+
+use super::{Command, Error, OutputFormat, Result};
+
+#[derive(Debug, PartialEq, Eq, Args, Clone)]
+pub struct Configuration {
+    #[command(subcommand)]
+    command: ConfigCommand,
+}
+
+#[derive(Debug, PartialEq, Eq, Subcommand, Clone)]
+pub enum ConfigCommand {
+    /// Print the effective configuration and where each value was set
+    Show {
+        /// Only show the values below this dotted key, e.g. `work.weekly_hours`
+        key: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Validate all configuration files, fails if any problem is found
+    Check {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Print the JSON Schema of the configuration files, for editor support
+    Schema,
+    /// Print the effective value of a dotted key
+    Get { key: String },
+    /// Set a dotted key in the user configuration of the selected profile
+    ///
+    /// Only files in the user config dir, or the one given by `--config-dir`,
+    /// are changed. TOML files keep their comments and layout, YAML and JSON
+    /// files are rewritten without comments and with sorted keys.
+    Set {
+        key: String,
+        /// Parsed like environment variables, e.g. `40`, `true` or `[a, b]`
+        value: String,
+        /// Write to this file in the user config dir instead of the one that
+        /// defines the key
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Remove a dotted key from the user configuration of the selected profile
+    ///
+    /// Only files in the user config dir, or the one given by `--config-dir`,
+    /// are changed. TOML files keep their comments and layout, YAML and JSON
+    /// files are rewritten without comments and with sorted keys.
+    Unset {
+        key: String,
+        /// Remove the key from this file in the user config dir instead of
+        /// the one that defines it
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+}
+
+impl Command for Configuration {
+    async fn run(&self, _progress: Arc<Root>, _args: &Cli, config: Config) -> Result<()> {
+        match self.command {
+            ConfigCommand::Show { ref key, format } => self.show(config, key.as_deref(), format),
+            ConfigCommand::Check { format } => self.check(config, format).await,
+            ConfigCommand::Schema => {
+                println!("{}", serde_json::to_string_pretty(&schema::schema())?);
+                Ok(())
+            }
+            ConfigCommand::Get { ref key } => self.get(config, key),
+            ConfigCommand::Set {
+                ref key,
+                ref value,
+                ref file,
+            } => self.set(config, key, value, file.clone()).await,
+            ConfigCommand::Unset { ref key, ref file } => {
+                self.unset(config, key, file.clone()).await
+            }
+        }
+    }
+}
+
+impl Configuration {
+    /// Checking, describing and repairing the configuration has to work while
+    /// it is broken.
+    pub fn tolerates_invalid_config(&self) -> bool {
+        matches!(
+            self.command,
+            ConfigCommand::Check { .. }
+                | ConfigCommand::Schema
+                | ConfigCommand::Set { .. }
+                | ConfigCommand::Unset { .. }
+        )
+    }
+
+    fn show(&self, config: Config, key: Option<&str>, format: OutputFormat) -> Result<()> {
+        let provenance = config.provenance(key)?;
+
+        match format {
+            OutputFormat::Text => print!("{}", render(&provenance)),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&provenance)?),
+        }
+
+        Ok(())
+    }
+
+    async fn check(&self, config: Config, format: OutputFormat) -> Result<()> {
+        let findings =
+            validate::validate(config.dirs().to_vec(), config.local().map(Path::to_owned)).await?;
+
+        match format {
+            OutputFormat::Text => findings.iter().for_each(|f| println!("{f}")),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&findings)?),
+        }
+
+        match findings.iter().filter(|f| f.is_problem()).count() {
+            0 => Ok(()),
+            n => Err(Error::ConfigProblems(n)),
+        }
+    }
+
+    fn get(&self, config: Config, key: &str) -> Result<()> {
+        let value = config
+            .figment()
+            .find_value(key)
+            .map_err(|_| ConfigError::UnknownKey(key.to_owned()))?;
+
+        match serde_json::to_value(value)? {
+            serde_json::Value::String(s) => println!("{s}"),
+            other => println!("{other}"),
+        }
+
+        Ok(())
+    }
+
+    async fn set(
+        &self,
+        config: Config,
+        key: &str,
+        value: &str,
+        file: Option<PathBuf>,
+    ) -> Result<()> {
+        let value: Value = value.parse().unwrap_or_else(|e| match e {});
+        validate::check_key(key, &value)?;
+        config.check_value(key, &value)?;
+
+        let (user, defining) = self.defining(&config, key).await?;
+        let file = match (file, defining.as_slice()) {
+            (Some(file), _) => edit::user_file(&user, &file)?,
+            (None, []) => {
+                edit::default_file(config.dirs()).ok_or(Error::MissingConfig("config dir"))?
+            }
+            (None, [file]) => file.clone(),
+            (None, files) => choose(key, files, io::stdin().is_terminal())?,
+        };
+
+        edit::set(
+            file.clone(),
+            config.profile().clone(),
+            key.to_owned(),
+            value,
+        )
+        .await?;
+        println!("{key} set in {}", file.display());
+
+        Ok(())
+    }
+
+    async fn unset(&self, config: Config, key: &str, file: Option<PathBuf>) -> Result<()> {
+        let (user, defining) = self.defining(&config, key).await?;
+        let file = match (file, defining.as_slice()) {
+            (Some(file), _) => edit::user_file(&user, &file)?,
+            (None, []) => return Err(Error::NotSet(key.to_owned())),
+            (None, [file]) => file.clone(),
+            (None, files) => choose(key, files, io::stdin().is_terminal())?,
+        };
+
+        match edit::unset(file.clone(), config.profile().clone(), key.to_owned()).await? {
+            true => println!("{key} removed from {}", file.display()),
+            false => return Err(Error::NotSet(key.to_owned())),
+        }
+
+        Ok(())
+    }
+
+    /// The user dir and its files that define `key`.
+    async fn defining(&self, config: &Config, key: &str) -> Result<(PathBuf, Vec<PathBuf>)> {
+        let user = edit::user_dir(config.dirs())
+            .ok_or(Error::MissingConfig("config dir"))?
+            .to_owned();
+        let files = edit::defining(user.clone(), config.profile().clone(), key.to_owned()).await?;
+
+        Ok((user, files))
+    }
+}
+
+/// Asks which of `files` to edit, if there is someone to ask.
+fn choose(key: &str, files: &[PathBuf], interactive: bool) -> Result<PathBuf> {
+    let ambiguous = || Error::AmbiguousFile(key.to_owned(), files.to_vec());
+
+    if !interactive {
+        return Err(ambiguous());
+    }
+
+    eprintln!("{key} is set in several files:");
+    for (i, file) in files.iter().enumerate() {
+        eprintln!("  {}: {}", i + 1, file.display());
+    }
+
+    loop {
+        eprint!("file to edit [1-{}]: ", files.len());
+
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).map_err(Error::Prompt)? == 0 {
+            return Err(ambiguous());
+        }
+
+        let chosen = answer
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| i.checked_sub(1))
+            .and_then(|i| files.get(i));
+        if let Some(file) = chosen {
+            return Ok(file.clone());
+        }
+    }
+}
+
+fn render(provenance: &[Provenance]) -> String {
+    let mut out = String::new();
+
+    for p in provenance {
+        let origin = match (&p.provider, &p.source) {
+            (Some(provider), Some(source)) => format!("{provider}: {source}"),
+            (Some(provider), None) => provider.clone(),
+            (None, Some(source)) => source.clone(),
+            (None, None) => "unknown".to_string(),
+        };
+
+        let _ = writeln!(out, "{} = {}  # {origin}", p.key, p.value);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use figment::Figment;
+    use prodash::tree::root::Options;
+    use rstest::*;
+
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+
+    #[test]
+    fn renders_origin_per_value() {
+        let provenance = vec![Provenance {
+            key: "data_dir".to_string(),
+            value: serde_json::json!("/tmp"),
+            provider: Some("TOML file".to_string()),
+            source: Some("/etc/timers/config.toml".to_string()),
+        }];
+
+        assert_eq!(
+            "data_dir = \"/tmp\"  # TOML file: /etc/timers/config.toml\n",
+            render(&provenance)
+        );
+    }
+
+    #[rstest]
+    #[case(OutputFormat::Text)]
+    #[case(OutputFormat::Json)]
+    #[tokio::test]
+    async fn show_unknown_key_fails(#[case] format: OutputFormat) {
+        let config: Config = Figment::new().try_into().unwrap();
+        let progress: Arc<_> = Options::default().create().into();
+        let show = Configuration {
+            command: ConfigCommand::Show {
+                key: Some("nope".to_string()),
+                format,
+            },
+        };
+
+        let result = show.run(progress, &Cli::default(), config).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Config(ConfigError::UnknownKey(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn check_fails_on_problems() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("config.toml")
+            .write_str("[default]\ndata_dri = \"/tmp\"\n")
+            .unwrap();
+        tmp.child("README.md").write_str("# Configuration").unwrap();
+        let config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+        let progress: Arc<_> = Options::default().create().into();
+        let check = Configuration {
+            command: ConfigCommand::Check {
+                format: OutputFormat::Text,
+            },
+        };
+
+        let result = check.run(progress, &Cli::default(), config).await;
+
+        assert!(matches!(result, Err(Error::ConfigProblems(1))));
+    }
+
+    #[tokio::test]
+    async fn check_ignores_unsupported_files() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("README.md").write_str("# Configuration").unwrap();
+        let config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+        let progress: Arc<_> = Options::default().create().into();
+        let check = Configuration {
+            command: ConfigCommand::Check {
+                format: OutputFormat::Json,
+            },
+        };
+
+        let result = check.run(progress, &Cli::default(), config).await;
+
+        assert!(result.is_ok());
+    }
+
+    fn command(command: ConfigCommand) -> Configuration {
+        Configuration { command }
+    }
+
+    async fn run(tmp: &TempDir, command: ConfigCommand) -> Result<()> {
+        let config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+        let progress: Arc<_> = Options::default().create().into();
+
+        self::command(command)
+            .run(progress, &Cli::default(), config)
+            .await
+    }
+
+    #[tokio::test]
+    async fn set_edits_the_defining_file() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("10-base.toml")
+            .write_str("[default.work]\nweekly_hours = 40\n")
+            .unwrap();
+        tmp.child("20-other.toml")
+            .write_str("[default]\ndata_dir = \"/tmp\"\n")
+            .unwrap();
+
+        let result = run(
+            &tmp,
+            ConfigCommand::Set {
+                key: "work.weekly_hours".to_string(),
+                value: "32".to_string(),
+                file: None,
+            },
+        )
+        .await;
+
+        assert!(result.is_ok());
+        tmp.child("10-base.toml")
+            .assert("[default.work]\nweekly_hours = 32\n");
+        tmp.child("20-other.toml")
+            .assert("[default]\ndata_dir = \"/tmp\"\n");
+    }
+
+    #[tokio::test]
+    async fn system_files_are_overridden_in_the_user_dir() {
+        let system = TempDir::new().unwrap();
+        let user = TempDir::new().unwrap();
+        system
+            .child("config.toml")
+            .write_str("[default.work]\nweekly_hours = 40\n")
+            .unwrap();
+        let config = Config::load(vec![system.to_path_buf(), user.to_path_buf()])
+            .await
+            .unwrap();
+        let set = command(ConfigCommand::Set {
+            key: "work.weekly_hours".to_string(),
+            value: "32".to_string(),
+            file: None,
+        });
+
+        let progress: Arc<_> = Options::default().create().into();
+
+        let result = set.run(progress, &Cli::default(), config).await;
+
+        assert!(result.is_ok());
+        system
+            .child("config.toml")
+            .assert("[default.work]\nweekly_hours = 40\n");
+        user.child(edit::DEFAULT_FILE)
+            .assert("[default.work]\nweekly_hours = 32\n");
+    }
+
+    #[tokio::test]
+    async fn files_outside_of_the_user_dir_are_refused() {
+        let tmp = TempDir::new().unwrap();
+        let elsewhere = TempDir::new().unwrap();
+
+        let result = run(
+            &tmp,
+            ConfigCommand::Set {
+                key: "work.weekly_hours".to_string(),
+                value: "32".to_string(),
+                file: Some(elsewhere.child("config.toml").to_path_buf()),
+            },
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Config(ConfigError::NotUserConfig(..)))
+        ));
+        assert!(!elsewhere.child("config.toml").exists());
+    }
+
+    #[tokio::test]
+    async fn set_rejects_invalid_values() {
+        let tmp = TempDir::new().unwrap();
+
+        let result = run(
+            &tmp,
+            ConfigCommand::Set {
+                key: "work.weekly_hours".to_string(),
+                value: "lots".to_string(),
+                file: None,
+            },
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::Config(_))));
+        assert!(!tmp.child(edit::DEFAULT_FILE).exists());
+    }
+
+    #[tokio::test]
+    async fn set_rejects_unknown_keys() {
+        let tmp = TempDir::new().unwrap();
+
+        let result = run(
+            &tmp,
+            ConfigCommand::Set {
+                key: "data_dri".to_string(),
+                value: "/x".to_string(),
+                file: None,
+            },
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Config(ConfigError::UnknownSetting(key, Some(suggestion))))
+                if key == "data_dri" && suggestion == "data_dir"
+        ));
+        assert!(!tmp.child(edit::DEFAULT_FILE).exists());
+    }
+
+    #[rstest]
+    #[case::set(
+        ConfigCommand::Set {
+            key: "work.weekly_hours".to_string(),
+            value: "32".to_string(),
+            file: None,
+        },
+        Some(32.0)
+    )]
+    #[case::unset(
+        ConfigCommand::Unset {
+            key: "work.weekly_hours".to_string(),
+            file: None,
+        },
+        None
+    )]
+    #[tokio::test]
+    async fn broken_configurations_can_be_repaired(
+        #[case] repair: ConfigCommand,
+        #[case] weekly_hours: Option<f64>,
+    ) {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(edit::DEFAULT_FILE)
+            .write_str(
+                "[freelance]\ndata_dir = \"/tmp\"\n[freelance.work]\nweekly_hours = \"lots\"\n",
+            )
+            .unwrap();
+        let load = || async {
+            let mut config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+            config.select_profile("freelance").map(|()| config)
+        };
+        assert!(load().await.is_err());
+        let repair = command(repair);
+        // as `main` does for commands that tolerate invalid configurations
+        let mut config = Config::unloaded(vec![tmp.to_path_buf()], None);
+        config.assume_profile("freelance");
+        let progress: Arc<_> = Options::default().create().into();
+
+        let result = repair.run(progress, &Cli::default(), config).await;
+
+        assert!(repair.tolerates_invalid_config());
+        assert!(result.is_ok());
+        assert_eq!(weekly_hours, load().await.unwrap().work.weekly_hours);
+    }
+
+    #[tokio::test]
+    async fn set_creates_the_default_file() {
+        let tmp = TempDir::new().unwrap();
+
+        let result = run(
+            &tmp,
+            ConfigCommand::Set {
+                key: "work.weekly_hours".to_string(),
+                value: "32".to_string(),
+                file: None,
+            },
+        )
+        .await;
+
+        assert!(result.is_ok());
+        tmp.child(edit::DEFAULT_FILE)
+            .assert("[default.work]\nweekly_hours = 32\n");
+    }
+
+    #[tokio::test]
+    async fn unset_requires_a_defining_file() {
+        let tmp = TempDir::new().unwrap();
+
+        let result = run(
+            &tmp,
+            ConfigCommand::Unset {
+                key: "work.weekly_hours".to_string(),
+                file: None,
+            },
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::NotSet(key)) if key == "work.weekly_hours"));
+    }
+
+    #[test]
+    fn ambiguous_files_need_a_terminal() {
+        let files = vec![PathBuf::from("a.toml"), PathBuf::from("b.toml")];
+
+        let result = choose("data_dir", &files, false);
+
+        assert!(matches!(result, Err(Error::AmbiguousFile(_, f)) if f == files));
+    }
+}
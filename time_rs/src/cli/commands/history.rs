@@ -0,0 +1,201 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{fmt::Write, sync::Arc};
+
+use clap::Args;
+use jiff::{civil::Date, tz::TimeZone};
+use prodash::tree::Root;
+
+use crate::{
+    cli::Cli,
+    config::Config,
+    data::{EntryChange, Revision, Store},
+};
+
+use super::{Command, Result};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Args)]
+pub struct History {
+    /// Only show changes of the entry with this id, e.g. `20261019T080000Z`
+    #[arg(long)]
+    pub entry: Option<String>,
+
+    /// Only show changes made on or after this day
+    #[arg(long)]
+    pub since: Option<Date>,
+}
+
+impl Command for History {
+    async fn run(&self, _progress: Arc<Root>, _args: &Cli, config: Config) -> Result<()> {
+        let store = Store::open(&config)?;
+        let tz = TimeZone::system();
+        let since = self
+            .since
+            .and_then(|since| since.to_zoned(tz.clone()).ok())
+            .map(|since| since.timestamp());
+
+        let revisions = filter(store.history(since).await?, self.entry.as_deref());
+        print!("{}", render(&revisions, &tz));
+
+        Ok(())
+    }
+}
+
+/// With an `entry`, only the revisions that changed it are kept, along with
+/// just its change.
+pub(crate) fn filter(revisions: Vec<Revision>, entry: Option<&str>) -> Vec<Revision> {
+    revisions
+        .into_iter()
+        .filter_map(|mut r| match entry {
+            Some(id) => {
+                r.changes.retain(|c| c.id() == id);
+                (!r.changes.is_empty()).then_some(r)
+            }
+            None => Some(r),
+        })
+        .collect()
+}
+
+/// Renders every revision with its changes, field changes are shown one per
+/// line.
+pub(crate) fn render(revisions: &[Revision], tz: &TimeZone) -> String {
+    let mut out = String::new();
+
+    for revision in revisions {
+        let _ = writeln!(
+            out,
+            "{} {} {}: {}",
+            revision.id.to_hex_with_len(7),
+            revision
+                .time
+                .to_zoned(tz.clone())
+                .strftime("%Y-%m-%d %H:%M"),
+            revision.author,
+            revision.message
+        );
+
+        for change in &revision.changes {
+            match change {
+                EntryChange::Added(entry) => {
+                    let _ = writeln!(out, "  added   {}", entry.id);
+                }
+                EntryChange::Changed { id, fields } => {
+                    let _ = writeln!(out, "  changed {id}");
+                    for field in fields {
+                        let _ = writeln!(
+                            out,
+                            "    {}: {} -> {}",
+                            field.field,
+                            field.old.as_deref().unwrap_or("(none)"),
+                            field.new.as_deref().unwrap_or("(none)")
+                        );
+                    }
+                }
+                EntryChange::Removed(entry) => {
+                    let _ = writeln!(out, "  removed {}", entry.id);
+                }
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::Figment;
+    use gix::ObjectId;
+    use jiff::Timestamp;
+
+    use super::*;
+    use crate::data::{tests::init_store, Change, Entry, FieldChange};
+
+    fn revision(time: &str, message: &str, changes: Vec<EntryChange>) -> Revision {
+        Revision {
+            id: ObjectId::null(gix::hash::Kind::Sha1),
+            author: "Jane".to_string(),
+            time: time.parse().unwrap(),
+            message: message.to_string(),
+            changes,
+        }
+    }
+
+    fn changed(id: &str) -> EntryChange {
+        EntryChange::Changed {
+            id: id.to_string(),
+            fields: vec![FieldChange {
+                field: "end".to_string(),
+                old: Some("2026-10-20T16:00:00Z".to_string()),
+                new: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn filter_by_entry() {
+        let revisions = vec![
+            revision(
+                "2026-10-22T08:00:00Z",
+                "edit",
+                vec![changed("a"), changed("b")],
+            ),
+            revision("2026-10-21T08:00:00Z", "other", vec![changed("b")]),
+            revision("2026-10-20T08:00:00Z", "start", vec![changed("a")]),
+        ];
+
+        let filtered = filter(revisions.clone(), Some("a"));
+        assert_eq!(vec!["edit", "start"], messages(&filtered));
+        assert_eq!(vec![changed("a")], filtered[0].changes);
+
+        let filtered = filter(revisions, None);
+        assert_eq!(vec!["edit", "other", "start"], messages(&filtered));
+        assert_eq!(2, filtered[0].changes.len());
+    }
+
+    fn messages(revisions: &[Revision]) -> Vec<&str> {
+        revisions.iter().map(|r| r.message.as_str()).collect()
+    }
+
+    #[test]
+    fn render_shows_field_changes() {
+        let entry = Entry::new(None, vec![], "2026-10-20T08:00:00Z".parse().unwrap());
+        let revisions = vec![revision(
+            "2026-10-22T08:00:00Z",
+            "fix tuesday",
+            vec![EntryChange::Added(entry), changed("20261019T080000Z")],
+        )];
+
+        assert_eq!(
+            "0000000 2026-10-22 08:00 Jane: fix tuesday\n\
+             \x20 added   20261020T080000Z\n\
+             \x20 changed 20261019T080000Z\n\
+             \x20   end: 2026-10-20T16:00:00Z -> (none)\n",
+            render(&revisions, &TimeZone::UTC)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_history_run() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let store = init_store(&temp);
+        let entry = Entry::new(None, vec![], Timestamp::now());
+        store
+            .commit("start", vec![Change::record(entry.path(), &entry).unwrap()])
+            .await
+            .unwrap();
+        let config: Config = Figment::new()
+            .merge(("data_dir", temp.path()))
+            .try_into()
+            .unwrap();
+        let history = History {
+            entry: Some(entry.id),
+            ..History::default()
+        };
+
+        let result = history.run(Root::new(), &Cli::default(), config).await;
+
+        assert!(result.is_ok());
+    }
+}
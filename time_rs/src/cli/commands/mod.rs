@@ -3,31 +3,77 @@
 // SPDX-License-Identifier: MIT
 
 use std::future::Future;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use clap::ValueEnum;
+use jiff::{SignedDuration, Timestamp};
 use prodash::tree::Root;
 use thiserror::Error as ThisError;
 
 use crate::cli::Cli;
-use crate::config::Config;
+use crate::config::{Config, Error as ConfigError};
+use crate::data::Error as DataError;
 
+mod absence;
+mod balance;
+mod budget;
+mod check;
+mod config;
+mod history;
+mod redo;
 mod repo;
 mod start;
 mod status;
 mod stop;
 mod summary;
+mod undo;
 
+pub use absence::Absence;
+pub use balance::Balance;
+pub use budget::Budget;
+pub use check::Check;
+pub use config::Configuration;
+pub use history::History;
+pub use redo::Redo;
 use repo::Error as RepoError;
 pub use repo::Repo;
 pub use start::Start;
 pub use status::Status;
 pub use stop::Stop;
 pub use summary::Summary;
+pub use undo::Undo;
 
 #[derive(Debug, ThisError)]
 pub enum Error {
     #[error("failed repo operation")]
     Repo(#[from] RepoError),
+    #[error("failed to load additional configuration")]
+    Config(#[from] ConfigError),
+    #[error("failed to access the time data")]
+    Data(#[from] DataError),
+    #[error("a timer is already running since {}", .0)]
+    AlreadyRunning(Timestamp),
+    #[error("no timer is running")]
+    NotRunning,
+    #[error("the absence overlaps with '{}', remove that one first", .0)]
+    OverlappingAbsence(String),
+    #[error("missing configuration value '{}'", .0)]
+    MissingConfig(&'static str),
+    #[error("found {} compliance violation(s)", .0)]
+    Violations(usize),
+    #[error("found {} problem(s) in the configuration", .0)]
+    ConfigProblems(usize),
+    #[error("found {} problem(s) in the repo", .0)]
+    RepoProblems(usize),
+    #[error("'{}' is set in several files, choose one with --file: {:?}", .0, .1)]
+    AmbiguousFile(String, Vec<PathBuf>),
+    #[error("'{}' is not set in any configuration file", .0)]
+    NotSet(String),
+    #[error("failed to read the answer")]
+    Prompt(#[source] std::io::Error),
+    #[error("failed to serialize the output")]
+    Json(#[from] serde_json::Error),
 }
 
 type Result<T> = std::result::Result<T, Error>;
@@ -41,3 +87,42 @@ pub trait Command {
         config: Config,
     ) -> impl Future<Output = Result<()>> + Send;
 }
+
+/// How commands that produce reports print them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Formats `duration` as hours and minutes, e.g. `7:30` or `-0:15`.
+pub(crate) fn format_duration(duration: SignedDuration, signed: bool) -> String {
+    let minutes = duration.as_secs() / 60;
+    let sign = match (minutes.is_negative(), signed) {
+        (true, _) => "-",
+        (false, true) => "+",
+        (false, false) => "",
+    };
+
+    format!("{sign}{}:{:02}", minutes.abs() / 60, minutes.abs() % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0, false, "0:00")]
+    #[case(0, true, "+0:00")]
+    #[case(450, false, "7:30")]
+    #[case(-15, false, "-0:15")]
+    #[case(-615, true, "-10:15")]
+    fn formats_durations(#[case] minutes: i64, #[case] signed: bool, #[case] expected: &str) {
+        let duration = SignedDuration::from_mins(minutes);
+
+        assert_eq!(expected, format_duration(duration, signed));
+    }
+}
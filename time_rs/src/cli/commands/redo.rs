@@ -0,0 +1,59 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::sync::Arc;
+
+use clap::Args;
+use prodash::tree::Root;
+
+use crate::{cli::Cli, config::Config, data::Store};
+
+use super::{undo::print_created, Command, Result};
+
+#[derive(Debug, PartialEq, Eq, Args)]
+pub struct Redo {
+    /// Number of undone changes to reapply
+    #[arg(default_value_t = 1)]
+    pub count: usize,
+}
+
+impl Command for Redo {
+    async fn run(&self, _progress: Arc<Root>, _args: &Cli, config: Config) -> Result<()> {
+        let store = Store::open(&config)?;
+
+        let created = store.redo(self.count).await?;
+        print_created(&store, created.len()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::Figment;
+    use jiff::Timestamp;
+
+    use super::*;
+    use crate::data::{tests::init_store, Change, Entry};
+
+    #[tokio::test]
+    async fn test_redo_run() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let store = init_store(&temp);
+        let entry = Entry::new(None, vec![], Timestamp::now());
+        store
+            .commit("start", vec![Change::record(entry.path(), &entry).unwrap()])
+            .await
+            .unwrap();
+        store.undo(1).await.unwrap();
+        let config: Config = Figment::new()
+            .merge(("data_dir", temp.path()))
+            .try_into()
+            .unwrap();
+        let redo = Redo { count: 1 };
+
+        let result = redo.run(Root::new(), &Cli::default(), config).await;
+
+        assert!(result.is_ok());
+        assert_eq!(vec![entry], store.entries().await.unwrap());
+    }
+}
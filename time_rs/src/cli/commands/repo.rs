@@ -2,23 +2,46 @@
 //
 // SPDX-License-Identifier: MIT
 
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeSet,
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use clap::{Args, Subcommand};
 use gix::{
     clone::PrepareFetch,
     create::{Kind, Options},
-    NestedProgress, Progress, ThreadSafeRepository,
+    remote::fetch::Shallow,
+    NestedProgress, Progress,
+};
+use jiff::{civil::Date, tz::TimeZone, Timestamp};
+use prodash::{
+    tree::{Item, Root},
+    unit::display::Mode,
 };
-use prodash::{tree::Root, unit::display::Mode};
 use tokio::{fs, task, time::sleep};
 use tokio_stream::{wrappers::ReadDirStream, StreamExt};
 
 pub use self::error::Error;
-use super::{Command, Result};
-use crate::{cli::Cli, config::Config};
+use super::{Command, Error as CommandError, History, Result};
+use crate::{
+    cli::Cli,
+    config::Config,
+    data::{layout, merge, Lock, Store, SyncState},
+};
 
+pub mod backup;
+pub mod bundle;
 pub mod error;
+pub mod fsck;
+pub mod init;
+pub mod sync;
 
 #[derive(Debug, PartialEq, Eq, Args, Clone)]
 pub struct Repo {
@@ -28,60 +51,226 @@ pub struct Repo {
 
 #[derive(Debug, PartialEq, Eq, Subcommand, Clone)]
 pub enum RepoCommand {
-    /// Initialise the repository
-    Init {},
+    /// Initialise the repository with the initial layout
+    Init {
+        /// Add the layout to the existing git repo in the data dir
+        #[arg(long)]
+        adopt: bool,
+        /// URL of the remote to configure as `origin`
+        #[arg(long)]
+        remote: Option<String>,
+    },
     /// synchronizes the repo to the configured remotes
-    Sync {},
+    Sync {
+        /// Record failures for `status` instead of reporting them, used by
+        /// the automatic sync
+        #[arg(long, hide = true)]
+        background: bool,
+    },
     /// Clone a repo from a remote
     Clone {
         /// URL
         url: String,
+        /// Branch to check out instead of the default branch of the remote
+        #[arg(long)]
+        branch: Option<String>,
+        /// Only fetch the last DEPTH commits
+        #[arg(long, conflicts_with = "shallow_since")]
+        depth: Option<NonZeroU32>,
+        /// Only fetch the commits since this date
+        #[arg(long)]
+        shallow_since: Option<Date>,
+        /// Folder to clone into, instead of the repo below the data dir
+        #[arg(long)]
+        into: Option<PathBuf>,
+    },
+    /// Delete the repo locally, after writing a backup below the data dir
+    Destroy {
+        /// Only list the files that would be removed
+        #[arg(long)]
+        dry_run: bool,
+        /// Do not write a backup first
+        #[arg(long)]
+        no_backup: bool,
+    },
+    /// Write a git bundle of the repo, into the backups below the data dir by default
+    Backup {
+        /// File to write the bundle to
+        path: Option<PathBuf>,
+    },
+    /// Bring back a repo from a backup or bundle, lists the backups without one
+    Restore {
+        /// Path or name of the backup or bundle
+        backup: Option<PathBuf>,
+    },
+    /// Check the repo and the time entries in it for problems
+    Fsck {
+        /// Commit a repair for each problem that can be fixed, requires '--force'
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Show the history of the time entries, same as `timers history`
+    Log(History),
+    /// Merge driver for records, run by git during merges
+    #[command(hide = true)]
+    Merge {
+        /// Common ancestor
+        base: PathBuf,
+        /// Our version, receives the result
+        ours: PathBuf,
+        /// Their version
+        theirs: PathBuf,
     },
-    /// Delete the repo locally
-    Destroy {},
 }
 
 impl Command for Repo {
     async fn run(&self, progress: Arc<Root>, args: &Cli, config: Config) -> Result<()> {
         match self.command {
-            RepoCommand::Init {} => self.init(config).await,
-            RepoCommand::Sync {} => self.sync().await,
+            RepoCommand::Init { .. } => self.init(config).await,
+            RepoCommand::Sync { background } => self.sync(background, config).await,
             RepoCommand::Clone { .. } => self.clone(progress, config).await,
-            RepoCommand::Destroy {} => self.destroy(progress, args, config).await,
+            RepoCommand::Destroy { .. } => self.destroy(progress, args, config).await,
+            RepoCommand::Backup { ref path } => self.backup(progress, path.clone(), config).await,
+            RepoCommand::Restore { ref backup } => {
+                self.restore(progress, backup.as_deref(), config).await
+            }
+            RepoCommand::Fsck { fix } => self.fsck(fix, args, config).await,
+            RepoCommand::Log(ref history) => history.run(progress, args, config).await,
+            RepoCommand::Merge {
+                ref base,
+                ref ours,
+                ref theirs,
+            } => self.merge(base, ours, theirs).await,
         }
     }
 }
 
 impl Repo {
+    /// Whether the subcommand works on the time data, instead of managing the
+    /// repository as a whole.
+    pub fn uses_data(&self) -> bool {
+        matches!(
+            self.command,
+            RepoCommand::Sync { background: false }
+                | RepoCommand::Fsck { .. }
+                | RepoCommand::Log(_)
+        )
+    }
+
+    /// Whether the subcommand checks `gix::interrupt::IS_INTERRUPTED` to roll
+    /// back on Ctrl-C. Other subcommands are simply terminated.
+    pub fn handles_interrupts(&self) -> bool {
+        matches!(
+            self.command,
+            RepoCommand::Clone { .. }
+                | RepoCommand::Destroy { .. }
+                | RepoCommand::Restore { .. }
+                | RepoCommand::Sync { .. }
+        )
+    }
+
+    /// Whether the subcommand changes the data dir. `merge` runs inside of a
+    /// git merge started by a command that holds the lock already, background
+    /// syncs take the lock themselves, to record when they can't.
+    pub fn mutates(&self) -> bool {
+        match self.command {
+            RepoCommand::Init { .. } | RepoCommand::Clone { .. } => true,
+            RepoCommand::Sync { background } => !background,
+            RepoCommand::Destroy { dry_run, .. } => !dry_run,
+            RepoCommand::Restore { ref backup } => backup.is_some(),
+            RepoCommand::Fsck { fix } => fix,
+            RepoCommand::Backup { .. } | RepoCommand::Log(_) | RepoCommand::Merge { .. } => false,
+        }
+    }
+
     async fn init(&self, config: Config) -> Result<()> {
+        let RepoCommand::Init { adopt, ref remote } = self.command else {
+            unreachable!("RepoCommand in init");
+        };
         let target_folder = config
             .data_dir
             .ok_or_else(|| Error::NoDataDir)?
             .join("repo");
+        let remote = remote
+            .as_deref()
+            .map(|url| gix::Url::try_from(url).map_err(|e| Error::GixUrlParse(e, url.to_string())))
+            .transpose()?;
 
-        fs::create_dir_all(&target_folder)
+        let folder = target_folder.clone();
+        task::spawn_blocking(move || init::init(&folder, adopt, remote).map(drop))
             .await
-            .map_err(Error::Io)?;
-
-        task::spawn_blocking(|| {
-            ThreadSafeRepository::init(
-                target_folder,
-                Kind::WithWorktree,
-                Options {
-                    destination_must_be_empty: true,
-                    ..Options::default()
-                },
-            )
-        })
-        .await
-        .map_err(Error::JoinError)?
-        .map_err(|e| Error::GixInit(Box::new(e)))?;
+            .map_err(Error::JoinError)??;
+
+        let store = Store::new(target_folder);
+        let changes = layout::seed(store.root())?;
+        if !changes.is_empty() {
+            let message = match adopt {
+                true => "add the timers layout",
+                false => "initialise the data repository",
+            };
+            store.commit(message, changes).await?;
+        }
+        store.migrate().await?;
 
         Ok(())
     }
 
-    async fn sync(&self) -> Result<()> {
-        Ok(())
+    /// Syncs the data repository. The foreground runs under the lock taken
+    /// for every mutating command, the background takes it itself. The
+    /// [`SyncState`] is only changed while holding the lock, the attempt is
+    /// saved before syncing, so interval syncs don't start again meanwhile.
+    async fn sync(&self, background: bool, config: Config) -> Result<()> {
+        let data_dir = config.data_dir.clone().ok_or(Error::NoDataDir)?;
+        let store = Store::open(&config)?;
+        let now = Timestamp::now();
+
+        let _lock = match background {
+            true => {
+                let lock = Lock::acquire(
+                    &data_dir,
+                    config.lock.timeout(),
+                    &gix::interrupt::IS_INTERRUPTED,
+                )
+                .await;
+                match lock {
+                    Ok(lock) => Some(lock),
+                    // without the lock the failure can't be recorded safely,
+                    // but it must not get lost either
+                    Err(e) => {
+                        let mut state = SyncState::load(&data_dir).await?;
+                        state.attempt(now);
+                        state.finish(now, &Err::<(), _>(e));
+                        state.save(&data_dir).await?;
+                        return Ok(());
+                    }
+                }
+            }
+            false => None,
+        };
+
+        let mut state = SyncState::load(&data_dir).await?;
+        state.attempt(now);
+        state.save(&data_dir).await?;
+
+        let result = async {
+            let targets = sync::targets(&config, store.root()).await?;
+            store
+                .sync(&targets, &gix::interrupt::IS_INTERRUPTED)
+                .await
+                .map_err(CommandError::from)
+        }
+        .await;
+
+        // failures in the foreground are reported right away
+        if background || result.is_ok() {
+            state.finish(now, &result);
+            state.save(&data_dir).await?;
+        }
+
+        match background {
+            true => Ok(()),
+            false => result,
+        }
     }
 
     async fn clone(&self, progress: Arc<Root>, config: Config) -> Result<()> {
@@ -89,25 +278,253 @@ impl Repo {
         let steps = prodash::unit::label_and_mode("steps", mode);
         let mut clone_progress = progress.add_child("clone");
         clone_progress.init(Some(4), Some(steps));
-        let fetch_progress = clone_progress.add_child("fetch");
-        let checkout_progress = clone_progress.add_child("checkout");
 
-        let data_dir = config.data_dir.ok_or_else(|| Error::NoDataDir)?;
-        let target_folder = data_dir.join("repo");
-
-        let RepoCommand::Clone { ref url } = self.command else {
+        let RepoCommand::Clone {
+            ref url,
+            ref branch,
+            depth,
+            shallow_since,
+            ref into,
+        } = self.command
+        else {
             unreachable!("RepoCommand in clone");
         };
+        let target_folder = match into {
+            Some(into) => into.clone(),
+            None => config
+                .data_dir
+                .ok_or_else(|| Error::NoDataDir)?
+                .join("repo"),
+        };
         let gix_url =
             gix::Url::try_from(url.as_str()).map_err(|e| Error::GixUrlParse(e, url.to_string()))?;
+        let shallow = match (depth, shallow_since) {
+            (Some(depth), _) => Shallow::DepthAtRemote(depth),
+            (None, Some(since)) => {
+                let cutoff = since
+                    .to_zoned(TimeZone::system())
+                    .map_err(|_| Error::InvalidDate(since))?
+                    .timestamp()
+                    .as_second();
+                Shallow::Since {
+                    cutoff: gix::date::Time::new(cutoff, 0),
+                }
+            }
+            (None, None) => Shallow::NoChange,
+        };
         clone_progress.inc();
 
-        fs::create_dir_all(&data_dir).await.map_err(Error::Io)?;
+        if let Some(parent) = target_folder.parent() {
+            fs::create_dir_all(parent).await.map_err(Error::Io)?;
+        }
         clone_progress.inc();
 
-        let mut fetch = PrepareFetch::new(
+        clone_into(
             gix_url,
+            &target_folder,
+            branch.as_deref(),
+            shallow,
+            &mut clone_progress,
+            &gix::interrupt::IS_INTERRUPTED,
+        )
+    }
+
+    async fn destroy(&self, progress: Arc<Root>, args: &Cli, config: Config) -> Result<()> {
+        let RepoCommand::Destroy { dry_run, no_backup } = self.command else {
+            unreachable!("RepoCommand in destroy");
+        };
+
+        let mode = Mode::with_throughput().and_percentage();
+        let files = prodash::unit::label_and_mode("files", mode);
+
+        let mut destroy_progress = progress.add_child("destroying repo");
+        destroy_progress.init(Some(0), Some(files));
+
+        if !args.force && !dry_run {
+            return Err(Error::DestructiveOperation("repo destroy".to_string()).into());
+        }
+
+        let data_dir = config.data_dir.ok_or_else(|| Error::NoDataDir)?;
+        let target_folder = data_dir.join("repo");
+        let backups = data_dir.join(backup::BACKUPS_DIR);
+
+        if dry_run {
+            let folder = target_folder.clone();
+            let files = task::spawn_blocking(move || backup::files(&folder))
+                .await
+                .map_err(Error::JoinError)??;
+            files.iter().for_each(|f| println!("{}", f.display()));
+            println!(
+                "would remove {} file(s) from {}",
+                files.len(),
+                target_folder.display()
+            );
+            if !no_backup {
+                println!("would write a backup to {}", backups.display());
+            }
+
+            return Ok(());
+        }
+
+        destroy_repo(
             target_folder,
+            (!no_backup).then_some(backups),
+            &mut destroy_progress,
+            &gix::interrupt::IS_INTERRUPTED,
+        )
+        .await?;
+
+        destroy_progress.done("destroyed".into());
+        sleep(Duration::from_millis(500)).await;
+
+        Ok(())
+    }
+
+    async fn backup(
+        &self,
+        progress: Arc<Root>,
+        path: Option<PathBuf>,
+        config: Config,
+    ) -> Result<()> {
+        let data_dir = config.data_dir.ok_or_else(|| Error::NoDataDir)?;
+        let file = path.unwrap_or_else(|| {
+            backup::unused(
+                &data_dir.join(backup::BACKUPS_DIR),
+                Timestamp::now(),
+                bundle::EXTENSION,
+            )
+        });
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent).await.map_err(Error::Io)?;
+        }
+
+        let mode = Mode::with_throughput().and_percentage();
+        let objects = prodash::unit::label_and_mode("objects", mode);
+        let mut backup_progress = progress.add_child("backup");
+        backup_progress.init(None, Some(objects));
+
+        let bundle = file.clone();
+        task::spawn_blocking(move || {
+            bundle::write(&data_dir.join("repo"), &bundle, &mut backup_progress)
+        })
+        .await
+        .map_err(Error::JoinError)??;
+        eprintln!("wrote bundle to {}", file.display());
+
+        Ok(())
+    }
+
+    async fn restore(
+        &self,
+        progress: Arc<Root>,
+        backup: Option<&Path>,
+        config: Config,
+    ) -> Result<()> {
+        let data_dir = config.data_dir.ok_or_else(|| Error::NoDataDir)?;
+        let backups = data_dir.join(backup::BACKUPS_DIR);
+
+        let Some(backup) = backup else {
+            let found = task::spawn_blocking(move || backup::list(&backups))
+                .await
+                .map_err(Error::JoinError)??;
+            found.iter().for_each(|b| println!("{}", b.display()));
+
+            return Ok(());
+        };
+
+        let mut restore_progress = progress.add_child("restore");
+        let backup = backup.to_owned();
+        task::spawn_blocking(move || {
+            let backup = backup::find(&backup, &backups)?;
+            let target = data_dir.join("repo");
+
+            match backup.to_string_lossy().ends_with(bundle::EXTENSION) {
+                true => bundle::restore(&backup, &target, &mut restore_progress),
+                false => backup::restore(&backup, &target),
+            }
+        })
+        .await
+        .map_err(Error::JoinError)??;
+
+        Ok(())
+    }
+
+    async fn merge(&self, base: &Path, ours: &Path, theirs: &Path) -> Result<()> {
+        let (base, ours, theirs) = (base.to_owned(), ours.to_owned(), theirs.to_owned());
+
+        task::spawn_blocking(move || merge::files(&base, &ours, &theirs))
+            .await
+            .map_err(Error::JoinError)??;
+
+        Ok(())
+    }
+
+    async fn fsck(&self, fix: bool, args: &Cli, config: Config) -> Result<()> {
+        if fix && !args.force {
+            return Err(Error::DestructiveOperation("repo fsck --fix".to_string()).into());
+        }
+
+        let store = Store::open(&config)?;
+        let root = store.root().to_owned();
+        let integrity = task::spawn_blocking(move || fsck::integrity(&root))
+            .await
+            .map_err(Error::JoinError)?;
+
+        let mut projects = config.projects.keys().cloned().collect::<BTreeSet<_>>();
+        projects.extend(store.budgets().await?.into_keys());
+
+        // repairs are only committed on top of an intact history, each one
+        // on its own, rechecking after every commit
+        let mut fixes = 0;
+        let consistency = loop {
+            let issues = fsck::consistency(&store.entry_files().await?, &projects, store.root());
+            let repair = issues.iter().find_map(|issue| issue.fix.clone());
+
+            match repair {
+                Some(repair) if fix && integrity.is_empty() && fixes < MAX_FIXES => {
+                    store.commit(repair.message.clone(), repair.changes).await?;
+                    println!("fixed: {}", repair.message);
+                    fixes += 1;
+                }
+                _ => break issues,
+            }
+        };
+
+        let issues = integrity.iter().chain(&consistency).collect::<Vec<_>>();
+        issues.iter().for_each(|issue| println!("{issue}"));
+
+        match issues.len() {
+            0 => Ok(()),
+            count => Err(CommandError::RepoProblems(count)),
+        }
+    }
+}
+
+/// Name the repo is moved to while being destroyed.
+const TRASH_DIR: &str = ".repo.destroying";
+
+/// Upper bound of repairs committed by a single `repo fsck --fix`.
+const MAX_FIXES: usize = 1000;
+
+/// Fetches `url` into `target` and checks it out, then makes sure it holds
+/// time data and configures the merge driver. On errors, including
+/// interrupts, nothing is left behind.
+fn clone_into(
+    url: gix::Url,
+    target: &Path,
+    branch: Option<&str>,
+    shallow: Shallow,
+    progress: &mut Item,
+    interrupt: &AtomicBool,
+) -> Result<()> {
+    let fetch_progress = progress.add_child("fetch");
+    let checkout_progress = progress.add_child("checkout");
+
+    // gix removes the target again when these are dropped before completion
+    {
+        let mut fetch = PrepareFetch::new(
+            url,
+            target,
             Kind::WithWorktree,
             Options {
                 destination_must_be_empty: true,
@@ -115,43 +532,90 @@ impl Repo {
             },
             Default::default(),
         )
-        .map_err(|e| Error::GixClone(Box::new(e)))?;
+        .map_err(|e| Error::GixClone(Box::new(e)))?
+        .with_shallow(shallow)
+        .with_ref_name(branch)
+        .map_err(|e| Error::InvalidBranch(branch.unwrap_or_default().to_owned(), e))?;
 
-        let (mut checkout, _fetch_outcome) = fetch
-            .fetch_then_checkout(fetch_progress, &gix::interrupt::IS_INTERRUPTED)
-            .map_err(|e| Error::GixFetch(Box::new(e)))?;
-        clone_progress.inc();
+        let (mut checkout, _fetch_outcome) =
+            fetch
+                .fetch_then_checkout(fetch_progress, interrupt)
+                .map_err(|e| or_interrupted(interrupt, Error::GixFetch(Box::new(e))))?;
+        progress.inc();
 
         let (_repo, _checkout_outcome) = checkout
-            .main_worktree(checkout_progress, &gix::interrupt::IS_INTERRUPTED)
-            .map_err(|e| Error::GixCheckout(Box::new(e)))?;
-        clone_progress.inc();
-
-        Ok(())
+            .main_worktree(checkout_progress, interrupt)
+            .map_err(|e| or_interrupted(interrupt, Error::GixCheckout(Box::new(e))))?;
+        progress.inc();
     }
 
-    async fn destroy(&self, progress: Arc<Root>, args: &Cli, config: Config) -> Result<()> {
-        let mode = Mode::with_throughput().and_percentage();
-        let files = prodash::unit::label_and_mode("files", mode);
+    // the clone is complete at this point, but might not be time data at all
+    let valid = match interrupt.load(Ordering::Relaxed) {
+        true => Err(Error::Interrupted.into()),
+        false => layout::validate(target).map_err(Into::into).and_then(|()| {
+            let repo =
+                gix::open(target).map_err(|e| Error::NotARepo(target.to_owned(), Box::new(e)))?;
+            init::merge_driver(&repo).map_err(Into::into)
+        }),
+    };
+    if valid.is_err() {
+        std::fs::remove_dir_all(target).map_err(Error::Io)?;
+    }
 
-        let mut destroy_progress = progress.add_child("destroying repo");
-        destroy_progress.init(Some(0), Some(files));
+    valid
+}
 
-        if !args.force {
-            return Err(Error::DestructiveOperation("repo destroy".to_string()).into());
+/// Writes a backup into `backups`, if given, then removes `repo`. Until the
+/// repo is moved out of the way, an interrupt removes the backup again and
+/// leaves the repo as it was. The removal itself is always completed.
+async fn destroy_repo<P>(
+    repo: PathBuf,
+    backups: Option<PathBuf>,
+    progress: &mut P,
+    interrupt: &AtomicBool,
+) -> Result<()>
+where
+    P: NestedProgress,
+    P::SubProgress: 'static,
+{
+    let trash = repo.with_file_name(TRASH_DIR);
+
+    let backup = match backups {
+        Some(backups) => {
+            let folder = repo.clone();
+            let backup =
+                task::spawn_blocking(move || backup::write(&folder, &backups, Timestamp::now()))
+                    .await
+                    .map_err(Error::JoinError)??;
+            Some(backup)
         }
+        None => None,
+    };
 
-        let target_folder = config
-            .data_dir
-            .ok_or_else(|| Error::NoDataDir)?
-            .join("repo");
+    if interrupt.load(Ordering::Relaxed) {
+        if let Some(backup) = backup {
+            fs::remove_file(backup).await.map_err(Error::Io)?;
+        }
+        return Err(Error::Interrupted.into());
+    }
+    if let Some(backup) = backup {
+        eprintln!("wrote backup to {}", backup.display());
+    }
 
-        remove_folder(target_folder, &mut destroy_progress).await?;
+    // left over by a destroy that got killed
+    if fs::try_exists(&trash).await.map_err(Error::Io)? {
+        fs::remove_dir_all(&trash).await.map_err(Error::Io)?;
+    }
+    fs::rename(&repo, &trash).await.map_err(Error::Io)?;
 
-        destroy_progress.done("destroyed".into());
-        sleep(Duration::from_millis(500)).await;
+    remove_folder(trash, progress).await
+}
 
-        Ok(())
+/// [`Error::Interrupted`] if `interrupt` is set, `error` otherwise.
+fn or_interrupted(interrupt: &AtomicBool, error: Error) -> Error {
+    match interrupt.load(Ordering::Relaxed) {
+        true => Error::Interrupted,
+        false => error,
     }
 }
 
@@ -201,6 +665,7 @@ mod tests {
     use super::*;
     use crate::cli::commands::Error as CommandError;
     use crate::cli::Commands;
+    use crate::data::{format, sync::tests as sync_tests, Change, Entry, Error as DataError};
 
     #[fixture]
     fn progress() -> Arc<Root> {
@@ -208,7 +673,13 @@ mod tests {
     }
 
     #[fixture]
-    fn cli_args(#[default(RepoCommand::Init {})] command: RepoCommand) -> Cli {
+    fn cli_args(
+        #[default(RepoCommand::Init {
+            adopt: false,
+            remote: None,
+        })]
+        command: RepoCommand,
+    ) -> Cli {
         Cli {
             command: Some(Commands::Repo(Repo { command })),
             ..Default::default()
@@ -218,7 +689,10 @@ mod tests {
     #[fixture]
     fn destroy_repo() -> Repo {
         Repo {
-            command: RepoCommand::Destroy {},
+            command: RepoCommand::Destroy {
+                dry_run: false,
+                no_backup: false,
+            },
         }
     }
 
@@ -226,9 +700,7 @@ mod tests {
     #[tokio::test]
     async fn clone_fails_without_data_dir(progress: Arc<Root>) {
         let repo = Repo {
-            command: RepoCommand::Clone {
-                url: "https://github.com/NobbZ/time_rs".to_string(),
-            },
+            command: clone("https://github.com/NobbZ/time_rs"),
         };
 
         let figment = Figment::new(); // No data_dir specified
@@ -249,9 +721,7 @@ mod tests {
         let tmp = assert_fs::TempDir::new().unwrap();
 
         let repo = Repo {
-            command: RepoCommand::Clone {
-                url: "not-a-valid-url".to_string(),
-            },
+            command: clone("not-a-valid-url"),
         };
 
         let figment = Figment::new().merge(("data_dir", tmp.path().to_str().unwrap()));
@@ -275,9 +745,7 @@ mod tests {
             .unwrap();
 
         let repo = Repo {
-            command: RepoCommand::Clone {
-                url: "https://github.com/NobbZ/time_rs".to_string(),
-            },
+            command: clone("https://github.com/NobbZ/time_rs"),
         };
 
         let figment = Figment::new().merge(("data_dir", tmp.path().to_str().unwrap()));
@@ -296,7 +764,10 @@ mod tests {
         let tmp = assert_fs::TempDir::new().unwrap();
 
         let repo = Repo {
-            command: RepoCommand::Init {},
+            command: RepoCommand::Init {
+                adopt: false,
+                remote: None,
+            },
         };
 
         let figment = Figment::new().merge(("data_dir", tmp.path().to_str().unwrap()));
@@ -307,6 +778,41 @@ mod tests {
 
         assert!(result.await.is_ok());
         assert!(tmp.child("repo").exists());
+        tmp.child("repo/FORMAT")
+            .assert(format!("{}\n", format::VERSION));
+        assert!(tmp.child("repo/.gitattributes").exists());
+        assert!(tmp.child("repo/README.md").exists());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn init_adopts_existing_repos(progress: Arc<Root>) {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let store = crate::data::tests::init_store(&tmp);
+        store
+            .commit("notes", vec![Change::Write("NOTES".into(), "mine".into())])
+            .await
+            .unwrap();
+        let repo = Repo {
+            command: RepoCommand::Init {
+                adopt: true,
+                remote: Some("https://example.com/time.git".to_string()),
+            },
+        };
+        let figment = Figment::new().merge(("data_dir", tmp.path().to_str().unwrap()));
+        let cli_args = cli_args(repo.command.clone());
+
+        let result = repo.run(progress, &cli_args, figment.try_into().unwrap());
+
+        assert!(result.await.is_ok());
+        tmp.child("repo/NOTES").assert("mine");
+        tmp.child("repo/FORMAT")
+            .assert(format!("{}\n", format::VERSION));
+        let history = store.history(None).await.unwrap();
+        assert_eq!("add the timers layout", history[0].message);
+        let git = gix::open(store.root()).unwrap();
+        assert!(!git.is_dirty().unwrap());
+        assert!(git.find_remote(init::REMOTE).is_ok());
     }
 
     #[rstest]
@@ -319,7 +825,10 @@ mod tests {
         let figment = Figment::new().merge(("data_dir", temp.path().to_str().unwrap()));
         let cli_args = Cli {
             command: Some(Commands::Repo(Repo {
-                command: RepoCommand::Destroy {},
+                command: RepoCommand::Destroy {
+                    dry_run: false,
+                    no_backup: false,
+                },
             })),
             force: false,
             ..Default::default()
@@ -352,7 +861,10 @@ mod tests {
         let figment = Figment::new().merge(("data_dir", temp.path().to_str().unwrap()));
         let cli_args = Cli {
             command: Some(Commands::Repo(Repo {
-                command: RepoCommand::Destroy {},
+                command: RepoCommand::Destroy {
+                    dry_run: false,
+                    no_backup: false,
+                },
             })),
             force: true,
             ..Default::default()
@@ -363,6 +875,95 @@ mod tests {
 
         assert!(result.await.is_ok());
         assert!(!repo_dir.exists());
+        assert_eq!(
+            1,
+            backup::list(&temp.child(backup::BACKUPS_DIR))
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn destroy_without_backup(progress: Arc<prodash::tree::Root>) {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("repo/test_file.txt").touch().unwrap();
+        let repo = Repo {
+            command: RepoCommand::Destroy {
+                dry_run: false,
+                no_backup: true,
+            },
+        };
+        let figment = Figment::new().merge(("data_dir", temp.path().to_str().unwrap()));
+        let cli_args = Cli {
+            force: true,
+            ..cli_args(repo.command.clone())
+        };
+        let config = figment.try_into().unwrap();
+
+        let result = repo.run(progress, &cli_args, config);
+
+        assert!(result.await.is_ok());
+        assert!(!temp.child("repo").exists());
+        assert!(!temp.child(backup::BACKUPS_DIR).exists());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn destroy_dry_run_keeps_the_repo(progress: Arc<prodash::tree::Root>) {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("repo/test_file.txt").touch().unwrap();
+        let repo = Repo {
+            command: RepoCommand::Destroy {
+                dry_run: true,
+                no_backup: false,
+            },
+        };
+        let figment = Figment::new().merge(("data_dir", temp.path().to_str().unwrap()));
+        let cli_args = cli_args(repo.command.clone());
+        let config = figment.try_into().unwrap();
+
+        let result = repo.run(progress, &cli_args, config);
+
+        assert!(result.await.is_ok());
+        assert!(temp.child("repo/test_file.txt").exists());
+        assert!(!temp.child(backup::BACKUPS_DIR).exists());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn restore_brings_back_destroyed_repos(
+        destroy_repo: Repo,
+        progress: Arc<prodash::tree::Root>,
+    ) {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("repo/test_file.txt")
+            .write_str("content")
+            .unwrap();
+        let figment = Figment::new().merge(("data_dir", temp.path().to_str().unwrap()));
+        let cli_args = Cli {
+            force: true,
+            ..cli_args(destroy_repo.command.clone())
+        };
+        destroy_repo
+            .run(
+                progress.clone(),
+                &cli_args,
+                figment.clone().try_into().unwrap(),
+            )
+            .await
+            .unwrap();
+        let backups = backup::list(&temp.child(backup::BACKUPS_DIR)).unwrap();
+        let restore = Repo {
+            command: RepoCommand::Restore {
+                backup: backups[0].file_name().map(PathBuf::from),
+            },
+        };
+
+        let result = restore.run(progress, &cli_args, figment.try_into().unwrap());
+
+        assert!(result.await.is_ok());
+        temp.child("repo/test_file.txt").assert("content");
     }
 
     #[rstest]
@@ -377,7 +978,10 @@ mod tests {
         let figment = Figment::new().merge(("data_dir", temp.path().to_str().unwrap()));
         let cli_args = Cli {
             command: Some(Commands::Repo(Repo {
-                command: RepoCommand::Destroy {},
+                command: RepoCommand::Destroy {
+                    dry_run: false,
+                    no_backup: false,
+                },
             })),
             force: true,
             ..Default::default()
@@ -390,6 +994,99 @@ mod tests {
         assert!(result.await.is_err());
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn backup_bundles_can_be_restored(progress: Arc<prodash::tree::Root>) {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let source = assert_fs::TempDir::new().unwrap();
+        let store = crate::data::tests::init_store(&source);
+        store
+            .commit("first", vec![Change::Write("a.toml".into(), "".into())])
+            .await
+            .unwrap();
+        let backup = Repo {
+            command: RepoCommand::Backup {
+                path: Some(temp.child("offline.bundle").to_path_buf()),
+            },
+        };
+        let restore = Repo {
+            command: RepoCommand::Restore {
+                backup: Some(temp.child("offline.bundle").to_path_buf()),
+            },
+        };
+        let cli_args = cli_args(backup.command.clone());
+        let source_config = Figment::new().merge(("data_dir", source.path().to_str().unwrap()));
+        let target_config = Figment::new().merge(("data_dir", temp.path().to_str().unwrap()));
+
+        backup
+            .run(
+                progress.clone(),
+                &cli_args,
+                source_config.try_into().unwrap(),
+            )
+            .await
+            .unwrap();
+        let result = restore.run(progress, &cli_args, target_config.try_into().unwrap());
+
+        assert!(result.await.is_ok());
+        assert!(temp.child("repo/a.toml").exists());
+        assert!(temp.child("repo/.git").exists());
+        assert!(merge_driver(temp.child("repo").path()).is_some());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn fsck_fixes_overlapping_entries(progress: Arc<prodash::tree::Root>) {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let store = crate::data::tests::init_store(&temp);
+        let mut first = Entry::new(None, vec![], "2026-10-19T08:00:00Z".parse().unwrap());
+        first.end = Some("2026-10-19T13:00:00Z".parse().unwrap());
+        let second = Entry::new(None, vec![], "2026-10-19T12:00:00Z".parse().unwrap());
+        let changes = [&first, &second]
+            .into_iter()
+            .map(|e| Change::record(e.path(), e).unwrap())
+            .collect();
+        store.commit("overlap", changes).await.unwrap();
+        let figment = Figment::new().merge(("data_dir", temp.path().to_str().unwrap()));
+        let check = Repo {
+            command: RepoCommand::Fsck { fix: false },
+        };
+        let fix = Repo {
+            command: RepoCommand::Fsck { fix: true },
+        };
+        let forced = Cli {
+            force: true,
+            ..cli_args(fix.command.clone())
+        };
+
+        let checked = check
+            .run(
+                progress.clone(),
+                &cli_args(check.command.clone()),
+                figment.clone().try_into().unwrap(),
+            )
+            .await;
+        let unforced = fix
+            .run(
+                progress.clone(),
+                &cli_args(fix.command.clone()),
+                figment.clone().try_into().unwrap(),
+            )
+            .await;
+        let fixed = fix
+            .run(progress, &forced, figment.try_into().unwrap())
+            .await;
+
+        assert!(matches!(checked, Err(CommandError::RepoProblems(1))));
+        assert!(matches!(
+            unforced,
+            Err(CommandError::Repo(Error::DestructiveOperation(_)))
+        ));
+        assert!(fixed.is_ok());
+        first.end = Some(second.start);
+        assert_eq!(vec![first, second], store.entries().await.unwrap());
+    }
+
     #[tokio::test]
     async fn remove_folder_empty_directory() -> eyre::Result<()> {
         let tmp = assert_fs::TempDir::new()?;
@@ -504,45 +1201,394 @@ mod tests {
         Ok(())
     }
 
-    #[rstest]
-    #[tokio::test]
-    async fn sync_succeeds(progress: Arc<Root>) {
+    /// Runs `repo init` in the data dir of `figment`, with the optional remote.
+    async fn init_data(figment: &Figment, remote: Option<String>) {
         let repo = Repo {
-            command: RepoCommand::Sync {},
+            command: RepoCommand::Init {
+                adopt: false,
+                remote,
+            },
         };
+        let cli_args = cli_args(repo.command.clone());
 
-        let figment = Figment::new();
-        let config: Config = figment.try_into().unwrap();
+        let config: Config = figment.extract().unwrap();
+        repo.run(progress(), &cli_args, config).await.unwrap();
+    }
+
+    async fn run_sync(figment: &Figment, background: bool) -> Result<()> {
+        let repo = Repo {
+            command: RepoCommand::Sync { background },
+        };
         let cli_args = cli_args(repo.command.clone());
 
-        let result = repo.run(progress, &cli_args, config);
+        let config: Config = figment.extract().unwrap();
+        repo.run(progress(), &cli_args, config).await
+    }
 
-        assert!(result.await.is_ok());
+    #[tokio::test]
+    async fn sync_pushes_to_the_remote_of_the_repo() {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let remote = sync_tests::bare(&tmp);
+        let figment = Figment::new().merge(("data_dir", tmp.path().to_str().unwrap()));
+        init_data(&figment, Some(remote.url.clone())).await;
+
+        run_sync(&figment, false).await.unwrap();
+
+        let local = gix::open(tmp.child("repo").path()).unwrap();
+        let pushed = gix::open(&remote.url).unwrap();
+        let branch = local.head_name().unwrap().unwrap();
+        assert_eq!(
+            local.head_id().unwrap().detach(),
+            pushed
+                .find_reference(branch.as_ref())
+                .unwrap()
+                .peel_to_id()
+                .unwrap()
+                .detach()
+        );
+        let state = SyncState::load(tmp.path()).await.unwrap();
+        assert!(state.attempted.is_some());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn attempts_are_saved_before_syncing() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let remote = sync_tests::bare(&tmp);
+        let figment = Figment::new().merge(("data_dir", tmp.path().to_str().unwrap()));
+        init_data(&figment, Some(remote.url.clone())).await;
+        // the remote runs the hook while the sync is pushing
+        let hook = Path::new(&remote.url).join("hooks").join("pre-receive");
+        let state = tmp.child(crate::data::sync::STATE_FILE);
+        let during = tmp.child("during");
+        during.create_dir_all().unwrap();
+        std::fs::write(
+            &hook,
+            format!(
+                "#!/bin/sh\ncp '{}' '{}'\n",
+                state.path().display(),
+                during.path().display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        run_sync(&figment, true).await.unwrap();
+
+        let during = SyncState::load(during.path()).await.unwrap();
+        let after = SyncState::load(tmp.path()).await.unwrap();
+        assert!(after.failures.is_empty());
+        assert_eq!(after.attempted, during.attempted);
+        assert!(during.attempted.is_some());
+    }
+
+    #[tokio::test]
+    async fn foreground_syncs_report_failures() {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let figment = Figment::new().merge(("data_dir", tmp.path().to_str().unwrap()));
+        init_data(&figment, None).await;
+
+        let result = run_sync(&figment, false).await;
+
+        assert!(matches!(
+            result,
+            Err(CommandError::Data(DataError::NoRemotes))
+        ));
+        let state = SyncState::load(tmp.path()).await.unwrap();
+        assert!(state.failures.is_empty());
+    }
+
+    #[tokio::test]
+    async fn background_syncs_queue_failures_until_they_succeed() {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let figment = Figment::new().merge(("data_dir", tmp.path().to_str().unwrap()));
+        init_data(&figment, None).await;
+
+        run_sync(&figment, true).await.unwrap();
+        run_sync(&figment, true).await.unwrap();
+
+        let state = SyncState::load(tmp.path()).await.unwrap();
+        assert_eq!(2, state.failures.len());
+        assert!(state.failures[0].error.contains("no remotes"));
+
+        let remote = sync_tests::bare(&tmp);
+        let figment = Figment::new()
+            .merge(("data_dir", tmp.path().to_str().unwrap()))
+            .merge(("remotes.origin.url", remote.url.as_str()));
+        run_sync(&figment, true).await.unwrap();
+
+        let state = SyncState::load(tmp.path()).await.unwrap();
+        assert!(state.failures.is_empty());
+        assert!(!tmp.child(crate::data::lock::LOCK_FILE).path().exists());
+    }
+
+    #[tokio::test]
+    async fn background_syncs_queue_lock_timeouts() {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let figment = Figment::new()
+            .merge(("data_dir", tmp.path().to_str().unwrap()))
+            .merge(("lock.timeout", 0));
+        init_data(&figment, None).await;
+        let _lock = Lock::acquire(tmp.path(), Duration::ZERO, &AtomicBool::new(false))
+            .await
+            .unwrap();
+
+        run_sync(&figment, true).await.unwrap();
+
+        let state = SyncState::load(tmp.path()).await.unwrap();
+        assert!(state.failures[0].error.contains("another timers process"));
+    }
+
+    /// The clone subcommand without any options.
+    fn clone(url: &str) -> RepoCommand {
+        RepoCommand::Clone {
+            url: url.to_string(),
+            branch: None,
+            depth: None,
+            shallow_since: None,
+            into: None,
+        }
+    }
+
+    /// A repo to clone from, with a commit of `files` at each of `dates`,
+    /// holding the number of the commit. `refs/heads/first` points to the
+    /// first commit.
+    fn fixture(files: &[&str], dates: &[&str]) -> (assert_fs::TempDir, String) {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let mut repo = gix::init(tmp.path()).unwrap();
+        repo.committer_or_set_generic_fallback().unwrap();
+        let mut parent = None;
+
+        for (n, date) in dates.iter().enumerate() {
+            let tree = repo.head_tree_id_or_empty().unwrap();
+            let mut editor = repo.edit_tree(tree).unwrap();
+            for file in files {
+                let content = match *file {
+                    format::FORMAT_FILE => format!("{}\n", format::VERSION),
+                    _ => format!("{n}"),
+                };
+                let blob = repo.write_blob(content).unwrap();
+                editor
+                    .upsert(*file, gix::objs::tree::EntryKind::Blob, blob)
+                    .unwrap();
+            }
+            let tree = editor.write().unwrap();
+            let time: Timestamp = date.parse().unwrap();
+            let signature = gix::actor::Signature {
+                name: "timers".into(),
+                email: "timers@example.com".into(),
+                time: gix::date::Time::new(time.as_second(), 0),
+            };
+            let mut buf = gix::date::parse::TimeBuf::default();
+            let signature = signature.to_ref(&mut buf);
+            let commit = repo
+                .commit_as(signature, signature, "HEAD", "edit", tree, parent)
+                .unwrap()
+                .detach();
+            if parent.is_none() {
+                repo.reference(
+                    "refs/heads/first",
+                    commit,
+                    gix::refs::transaction::PreviousValue::Any,
+                    "fixture",
+                )
+                .unwrap();
+            }
+            parent = Some(commit);
+        }
+
+        let url = format!("file://{}", tmp.path().display());
+        (tmp, url)
+    }
+
+    const DATA: &[&str] = &["FORMAT", "entries/2026/10/a.toml"];
+
+    const DATES: &[&str] = &[
+        "2020-01-01T00:00:00Z",
+        "2025-01-01T00:00:00Z",
+        "2026-01-01T00:00:00Z",
+    ];
+
+    async fn run_clone(progress: Arc<Root>, command: RepoCommand, data_dir: &Path) -> Result<()> {
+        let repo = Repo { command };
+        let figment = Figment::new().merge(("data_dir", data_dir.to_str().unwrap()));
+        let cli_args = cli_args(repo.command.clone());
+
+        repo.run(progress, &cli_args, figment.try_into().unwrap())
+            .await
+    }
+
+    /// The merge driver configured in `repo`, if any.
+    fn merge_driver(repo: &Path) -> Option<String> {
+        let repo = gix::open(repo).unwrap();
+        let driver = repo
+            .config_snapshot()
+            .string("merge.timers.driver")
+            .map(|driver| driver.to_string());
+
+        driver
+    }
+
+    fn commits(repo: &Path) -> usize {
+        let repo = gix::open(repo).unwrap();
+        let head = repo.head_id().unwrap();
+
+        head.ancestors().all().unwrap().count()
     }
 
     #[rstest]
     #[tokio::test]
     async fn clone_succeeds(progress: Arc<Root>) {
         let tmp = assert_fs::TempDir::new().unwrap();
+        let (_remote, url) = fixture(DATA, DATES);
 
+        let result = run_clone(progress, clone(&url), tmp.path()).await;
+
+        assert!(result.is_ok());
+        tmp.child("repo/entries/2026/10/a.toml").assert("2");
+        assert_eq!(3, commits(tmp.child("repo").path()));
+        assert!(merge_driver(tmp.child("repo").path()).is_some());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn clone_checks_out_the_branch(progress: Arc<Root>) {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let (_remote, url) = fixture(DATA, DATES);
+        let command = RepoCommand::Clone {
+            url,
+            branch: Some("first".to_string()),
+            depth: None,
+            shallow_since: None,
+            into: None,
+        };
+
+        let result = run_clone(progress, command, tmp.path()).await;
+
+        assert!(result.is_ok());
+        tmp.child("repo/entries/2026/10/a.toml").assert("0");
+        let repo = gix::open(tmp.child("repo").path()).unwrap();
+        assert_eq!(
+            "refs/heads/first",
+            repo.head_name().unwrap().unwrap().as_bstr()
+        );
+    }
+
+    #[rstest]
+    #[case::depth(Some(NonZeroU32::MIN), None, 1)]
+    #[case::since(None, Some("2024-06-01"), 2)]
+    #[tokio::test]
+    async fn clone_can_be_shallow(
+        progress: Arc<Root>,
+        #[case] depth: Option<NonZeroU32>,
+        #[case] shallow_since: Option<&str>,
+        #[case] expected: usize,
+    ) {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let (_remote, url) = fixture(DATA, DATES);
+        let command = RepoCommand::Clone {
+            url,
+            branch: None,
+            depth,
+            shallow_since: shallow_since.map(|d| d.parse().unwrap()),
+            into: None,
+        };
+
+        let result = run_clone(progress, command, tmp.path()).await;
+
+        assert!(result.is_ok());
+        let repo = gix::open(tmp.child("repo").path()).unwrap();
+        assert!(repo.is_shallow());
+        assert_eq!(expected, commits(tmp.child("repo").path()));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn clone_into_a_custom_folder(progress: Arc<Root>) {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let (_remote, url) = fixture(DATA, DATES);
         let repo = Repo {
             command: RepoCommand::Clone {
-                url: "https://github.com/git-fixtures/basic".to_string(),
+                url,
+                branch: None,
+                depth: None,
+                shallow_since: None,
+                into: Some(tmp.child("elsewhere").to_path_buf()),
             },
         };
-
-        let figment = Figment::new().merge(("data_dir", tmp.path().to_str().unwrap()));
-        let config: Config = figment.try_into().unwrap();
+        let config: Config = Figment::new().try_into().unwrap();
         let cli_args = cli_args(repo.command.clone());
 
         let result = repo.run(progress, &cli_args, config).await;
 
-        if let Err(e) = &result {
-            eprintln!("{:?}", e);
-        }
-
         assert!(result.is_ok());
-        assert!(tmp.child("repo").exists());
-        assert!(tmp.child("repo/.git").exists());
+        tmp.child("elsewhere/entries/2026/10/a.toml").assert("2");
+        assert!(!tmp.child("repo").exists());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn interrupted_clones_leave_nothing_behind(progress: Arc<Root>) {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let (_remote, url) = fixture(DATA, DATES);
+        let mut item = progress.add_child("clone");
+
+        let result = clone_into(
+            url.as_str().try_into().unwrap(),
+            tmp.child("repo").path(),
+            None,
+            Shallow::NoChange,
+            &mut item,
+            &AtomicBool::new(true),
+        );
+
+        assert!(matches!(
+            result,
+            Err(CommandError::Repo(Error::Interrupted))
+        ));
+        assert!(!tmp.child("repo").exists());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn interrupted_destroys_keep_the_repo(progress: Arc<Root>) {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        tmp.child("repo/test_file.txt")
+            .write_str("content")
+            .unwrap();
+        let backups = tmp.child(backup::BACKUPS_DIR);
+        let mut item = progress.add_child("destroy");
+
+        let result = super::destroy_repo(
+            tmp.child("repo").to_path_buf(),
+            Some(backups.to_path_buf()),
+            &mut item,
+            &AtomicBool::new(true),
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(CommandError::Repo(Error::Interrupted))
+        ));
+        tmp.child("repo/test_file.txt").assert("content");
+        assert_eq!(Vec::<PathBuf>::new(), backup::list(&backups).unwrap());
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn clone_removes_unrelated_repos(progress: Arc<Root>) {
+        let tmp = assert_fs::TempDir::new().unwrap();
+        let (_remote, url) = fixture(&["Cargo.toml", "src/main.rs"], DATES);
+
+        let result = run_clone(progress, clone(&url), tmp.path()).await;
+
+        assert!(matches!(
+            result,
+            Err(CommandError::Data(crate::data::Error::NotADataRepo(_)))
+        ));
+        assert!(!tmp.child("repo").exists());
     }
 }
@@ -0,0 +1,273 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Backups of the data repository, written before it gets destroyed.
+
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use jiff::Timestamp;
+
+use super::{bundle, Error};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Folder below the data dir that holds the backups.
+pub const BACKUPS_DIR: &str = "backups";
+
+/// File extension of backups.
+pub const EXTENSION: &str = ".tar.gz";
+
+/// Suffix of backups while they are written, they get their final name
+/// only once complete.
+pub const PARTIAL: &str = ".partial";
+
+/// Writes a compressed tarball of the whole `repo` folder, including
+/// uncommitted changes, into `backups`.
+pub fn write(repo: &Path, backups: &Path, now: Timestamp) -> Result<PathBuf> {
+    // fails for missing repos, before an empty backup is created
+    fs::read_dir(repo)?;
+    fs::create_dir_all(backups)?;
+
+    let file = unused(backups, now, EXTENSION);
+    let partial = partial(&file);
+    let written = File::create_new(&partial)
+        .map_err(Error::from)
+        .and_then(|out| {
+            let mut archive = tar::Builder::new(GzEncoder::new(out, Compression::default()));
+            archive.append_dir_all(".", repo)?;
+            archive.into_inner()?.finish()?.sync_all()?;
+            Ok(())
+        });
+    if let Err(e) = written.and_then(|()| Ok(fs::rename(&partial, &file)?)) {
+        // a truncated backup would be restored without complaint
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    Ok(file)
+}
+
+/// The first free name in `backups` for a backup written at `now`,
+/// backups of the same second are numbered and sort after each other.
+pub fn unused(backups: &Path, now: Timestamp, extension: &str) -> PathBuf {
+    let mut n = 0;
+    loop {
+        let file = backups.join(file_name(now, n, extension));
+        if !file.exists() && !partial(&file).exists() {
+            return file;
+        }
+        n += 1;
+    }
+}
+
+/// Name of the `n`th backup written at `now`.
+fn file_name(now: Timestamp, n: usize, extension: &str) -> String {
+    let time = now.strftime("%Y%m%dT%H%M%SZ");
+    match n {
+        0 => format!("repo-{time}{extension}"),
+        n => format!("repo-{time}_{n:03}{extension}"),
+    }
+}
+
+/// Where `file` is written until it is complete.
+pub(crate) fn partial(file: &Path) -> PathBuf {
+    let mut partial = file.as_os_str().to_owned();
+    partial.push(PARTIAL);
+    partial.into()
+}
+
+/// Unpacks `backup` into `repo`, which must not exist or be empty.
+pub fn restore(backup: &Path, repo: &Path) -> Result<()> {
+    if repo.exists() && fs::read_dir(repo)?.next().is_some() {
+        return Err(Error::RepoExists(repo.to_owned()));
+    }
+
+    let result = unpack(backup, repo);
+    if result.is_err() {
+        let _ = fs::remove_dir_all(repo);
+    }
+
+    result
+}
+
+fn unpack(backup: &Path, repo: &Path) -> Result<()> {
+    fs::create_dir_all(repo)?;
+    tar::Archive::new(GzDecoder::new(File::open(backup)?)).unpack(repo)?;
+
+    Ok(())
+}
+
+/// The backups and bundles in `backups`, oldest first.
+pub fn list(backups: &Path) -> Result<Vec<PathBuf>> {
+    if !backups.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut found = fs::read_dir(backups)?
+        .map(|entry| Ok(entry?.path()))
+        .filter(|path| {
+            path.as_ref().map_or(true, |p| {
+                let name = p.to_string_lossy();
+                name.ends_with(EXTENSION) || name.ends_with(bundle::EXTENSION)
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    found.sort();
+
+    Ok(found)
+}
+
+/// `backup` as given, or the backup of that name in `backups`.
+pub fn find(backup: &Path, backups: &Path) -> Result<PathBuf> {
+    [backup.to_owned(), backups.join(backup)]
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| Error::UnknownBackup(backup.to_owned()))
+}
+
+/// Every file below `dir`, as removed by a destroy.
+pub fn files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        match entry.file_type()?.is_dir() {
+            true => files.extend(self::files(&entry.path())?),
+            false => files.push(entry.path()),
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+
+    const NOW: &str = "2026-10-19T08:00:00Z";
+
+    fn repo(tmp: &TempDir) -> PathBuf {
+        let repo = tmp.child("repo");
+        repo.child("entries/2026/10/a.toml")
+            .write_str("start = 1")
+            .unwrap();
+        repo.child(".git/HEAD").write_str("ref: main").unwrap();
+        repo.to_path_buf()
+    }
+
+    #[test]
+    fn backups_restore_every_file() {
+        let tmp = TempDir::new().unwrap();
+        let repo = repo(&tmp);
+        let backups = tmp.child(BACKUPS_DIR);
+
+        let backup = write(&repo, &backups, NOW.parse().unwrap()).unwrap();
+        fs::remove_dir_all(&repo).unwrap();
+        restore(&backup, &repo).unwrap();
+
+        assert_eq!(backups.child("repo-20261019T080000Z.tar.gz").path(), backup);
+        tmp.child("repo/entries/2026/10/a.toml").assert("start = 1");
+        tmp.child("repo/.git/HEAD").assert("ref: main");
+    }
+
+    #[test]
+    fn restore_refuses_existing_repos() {
+        let tmp = TempDir::new().unwrap();
+        let repo = repo(&tmp);
+        let backup = write(&repo, tmp.child(BACKUPS_DIR).path(), NOW.parse().unwrap()).unwrap();
+
+        assert!(matches!(restore(&backup, &repo), Err(Error::RepoExists(_))));
+    }
+
+    #[test]
+    fn truncated_backups_leave_no_repo_behind() {
+        let tmp = TempDir::new().unwrap();
+        let repo = repo(&tmp);
+        let backup = write(&repo, tmp.child(BACKUPS_DIR).path(), NOW.parse().unwrap()).unwrap();
+        let content = fs::read(&backup).unwrap();
+        let truncated = tmp.child("truncated.tar.gz");
+        truncated
+            .write_binary(&content[..content.len() / 2])
+            .unwrap();
+        fs::remove_dir_all(&repo).unwrap();
+
+        assert!(restore(&truncated, &repo).is_err());
+        assert!(!repo.exists());
+
+        restore(&backup, &repo).unwrap();
+        tmp.child("repo/entries/2026/10/a.toml").assert("start = 1");
+    }
+
+    #[test]
+    fn backups_of_the_same_second_are_numbered() {
+        let tmp = TempDir::new().unwrap();
+        let repo = repo(&tmp);
+        let backups = tmp.child(BACKUPS_DIR);
+
+        let written = (0..3)
+            .map(|_| write(&repo, &backups, NOW.parse().unwrap()).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                backups.child("repo-20261019T080000Z.tar.gz").to_path_buf(),
+                backups
+                    .child("repo-20261019T080000Z_001.tar.gz")
+                    .to_path_buf(),
+                backups
+                    .child("repo-20261019T080000Z_002.tar.gz")
+                    .to_path_buf(),
+            ],
+            written
+        );
+        assert_eq!(written, list(&backups).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_backups_leave_nothing_behind() {
+        let tmp = TempDir::new().unwrap();
+        let repo = repo(&tmp);
+        let backups = tmp.child(BACKUPS_DIR);
+        // symlinks are followed, so a dangling one fails the archive
+        std::os::unix::fs::symlink(tmp.child("gone"), repo.join("dangling")).unwrap();
+
+        assert!(write(&repo, &backups, NOW.parse().unwrap()).is_err());
+        assert_eq!(0, fs::read_dir(&backups).unwrap().count());
+    }
+
+    #[test]
+    fn backups_are_found_by_name() {
+        let tmp = TempDir::new().unwrap();
+        let backups = tmp.child(BACKUPS_DIR);
+        backups.child("repo-1.tar.gz").touch().unwrap();
+        backups.child("repo-0.tar.gz").touch().unwrap();
+        backups.child("repo-2.bundle").touch().unwrap();
+        backups.child("notes.txt").touch().unwrap();
+
+        assert_eq!(
+            vec![
+                backups.child("repo-0.tar.gz").to_path_buf(),
+                backups.child("repo-1.tar.gz").to_path_buf(),
+                backups.child("repo-2.bundle").to_path_buf(),
+            ],
+            list(&backups).unwrap()
+        );
+        assert_eq!(
+            backups.child("repo-1.tar.gz").to_path_buf(),
+            find(Path::new("repo-1.tar.gz"), &backups).unwrap()
+        );
+        assert!(matches!(
+            find(Path::new("repo-3.tar.gz"), &backups),
+            Err(Error::UnknownBackup(_))
+        ));
+    }
+}
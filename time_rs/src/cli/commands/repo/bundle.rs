@@ -0,0 +1,473 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Self-contained git bundles of the data repository, for backups and for
+//! moving the data to machines without access to a remote.
+//!
+//! Bundles are written in the v2 format that `git bundle` uses as well: a
+//! header listing the refs, followed by a pack of every object reachable
+//! from them. The pack is stored without deltas.
+
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use flate2::{write::ZlibEncoder, Compression};
+use gix::{
+    create::{Kind, Options},
+    objs::{find::Never, Kind as ObjectKind},
+    odb::pack,
+    refs::{
+        transaction::{Change, LogChange, PreviousValue, RefEdit},
+        Target,
+    },
+    traverse::tree::Recorder,
+    NestedProgress, ObjectId, Progress, Repository, ThreadSafeRepository,
+};
+
+use super::{backup, init, Error};
+use crate::data::history::blobs;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// File extension of bundles.
+pub const EXTENSION: &str = ".bundle";
+
+const SIGNATURE: &str = "# v2 git bundle";
+
+fn bundle_error<E>(e: E) -> Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    Error::GixBundle(Box::new(e))
+}
+
+/// Writes every local branch and `HEAD` of the repository in `repo` to
+/// `file`, returns the number of objects. The bundle gets its name only once
+/// it is complete.
+pub fn write<P>(repo: &Path, file: &Path, progress: &mut P) -> Result<usize>
+where
+    P: Progress,
+{
+    let repo = &gix::open(repo).map_err(bundle_error)?;
+    let refs = refs(repo)?;
+    let objects = objects(repo, refs.iter().map(|(id, _)| *id))?;
+    progress.init(Some(objects.len()), None);
+
+    if file.exists() {
+        return Err(io::Error::from(io::ErrorKind::AlreadyExists).into());
+    }
+    let partial = backup::partial(file);
+    let written = File::create_new(&partial)
+        .map_err(Error::from)
+        .and_then(|out| write_to(repo, &refs, &objects, out, progress));
+    if let Err(e) = written.and_then(|()| Ok(fs::rename(&partial, file)?)) {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+
+    Ok(objects.len())
+}
+
+fn write_to<P>(
+    repo: &Repository,
+    refs: &[(ObjectId, String)],
+    objects: &BTreeSet<ObjectId>,
+    out: File,
+    progress: &mut P,
+) -> Result<()>
+where
+    P: Progress,
+{
+    let mut out = BufWriter::new(out);
+    writeln!(out, "{SIGNATURE}")?;
+    for (id, name) in refs {
+        writeln!(out, "{id} {name}")?;
+    }
+    writeln!(out)?;
+
+    let mut pack = HashingWriter {
+        inner: out,
+        hasher: gix::hash::hasher(repo.object_hash()),
+    };
+    pack.write_all(b"PACK")?;
+    pack.write_all(&2u32.to_be_bytes())?;
+    pack.write_all(
+        &u32::try_from(objects.len())
+            .unwrap_or(u32::MAX)
+            .to_be_bytes(),
+    )?;
+    for id in objects {
+        let object = repo.find_object(*id).map_err(bundle_error)?;
+        write_entry(&mut pack, object.kind, &object.data)?;
+        progress.inc();
+    }
+
+    let checksum = pack.hasher.try_finalize().map_err(bundle_error)?;
+    let mut out = pack.inner;
+    out.write_all(checksum.as_bytes())?;
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+
+    Ok(())
+}
+
+/// `HEAD` and the local branches, with the branch `HEAD` points to first.
+fn refs(repo: &Repository) -> Result<Vec<(ObjectId, String)>> {
+    let head = repo.head_name().map_err(bundle_error)?;
+    let mut refs = vec![];
+
+    let platform = repo.references().map_err(bundle_error)?;
+    for reference in platform.local_branches().map_err(bundle_error)? {
+        let reference = reference.map_err(Error::GixBundle)?;
+        let Some(id) = reference.try_id() else {
+            continue;
+        };
+        let entry = (id.detach(), reference.name().as_bstr().to_string());
+
+        match head
+            .as_ref()
+            .is_some_and(|head| head.as_ref() == reference.name())
+        {
+            true => refs.insert(0, entry),
+            false => refs.push(entry),
+        }
+    }
+
+    if let Ok(id) = repo.head_id() {
+        refs.insert(0, (id.detach(), "HEAD".to_string()));
+    }
+    if refs.is_empty() {
+        return Err(Error::EmptyRepo);
+    }
+
+    Ok(refs)
+}
+
+/// Every object reachable from `tips`.
+pub(crate) fn objects(
+    repo: &Repository,
+    tips: impl Iterator<Item = ObjectId>,
+) -> Result<BTreeSet<ObjectId>> {
+    let mut objects = BTreeSet::new();
+    let tips = tips.collect::<BTreeSet<_>>();
+
+    for info in repo.rev_walk(tips).all().map_err(bundle_error)? {
+        let commit = info.map_err(bundle_error)?.object().map_err(bundle_error)?;
+        let tree = commit.tree().map_err(bundle_error)?;
+        objects.insert(commit.id);
+        if !objects.insert(tree.id) {
+            continue;
+        }
+
+        let mut recorder = Recorder::default();
+        tree.traverse()
+            .breadthfirst(&mut recorder)
+            .map_err(bundle_error)?;
+        objects.extend(
+            recorder
+                .records
+                .into_iter()
+                .filter(|r| !r.mode.is_commit())
+                .map(|r| r.oid),
+        );
+    }
+
+    Ok(objects)
+}
+
+/// Writes a single undeltified pack entry.
+fn write_entry(out: &mut impl Write, kind: ObjectKind, data: &[u8]) -> io::Result<()> {
+    let kind: u8 = match kind {
+        ObjectKind::Commit => 1,
+        ObjectKind::Tree => 2,
+        ObjectKind::Blob => 3,
+        ObjectKind::Tag => 4,
+    };
+
+    let mut size = data.len();
+    let mut byte = (kind << 4) | (size & 0x0f) as u8;
+    size >>= 4;
+    while size > 0 {
+        out.write_all(&[byte | 0x80])?;
+        byte = (size & 0x7f) as u8;
+        size >>= 7;
+    }
+    out.write_all(&[byte])?;
+
+    let mut encoder = ZlibEncoder::new(out, Compression::default());
+    encoder.write_all(data)?;
+    encoder.finish()?;
+
+    Ok(())
+}
+
+struct HashingWriter<W> {
+    inner: W,
+    hasher: gix::hash::Hasher,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Creates a repository with a worktree and the merge driver at `target`
+/// from `bundle`. A partially restored `target` is removed again.
+pub fn restore<P>(bundle: &Path, target: &Path, progress: &mut P) -> Result<()>
+where
+    P: NestedProgress,
+    P::SubProgress: 'static,
+{
+    if target.exists() && fs::read_dir(target)?.next().is_some() {
+        return Err(Error::RepoExists(target.to_owned()));
+    }
+
+    let result = unbundle(bundle, target, progress);
+    if result.is_err() {
+        let _ = fs::remove_dir_all(target);
+    }
+
+    result
+}
+
+fn unbundle<P>(bundle: &Path, target: &Path, progress: &mut P) -> Result<()>
+where
+    P: NestedProgress,
+    P::SubProgress: 'static,
+{
+    let mut reader = BufReader::new(File::open(bundle)?);
+    let refs = header(&mut reader).ok_or_else(|| Error::InvalidBundle(bundle.to_owned()))?;
+
+    fs::create_dir_all(target)?;
+    let mut repo = ThreadSafeRepository::init(
+        target,
+        Kind::WithWorktree,
+        Options {
+            destination_must_be_empty: true,
+            ..Options::default()
+        },
+    )
+    .map_err(Box::new)?
+    .to_thread_local();
+    // ref logs need a committer
+    repo.committer_or_set_generic_fallback()
+        .map_err(bundle_error)?;
+
+    let pack_dir = repo.git_dir().join("objects").join("pack");
+    fs::create_dir_all(&pack_dir)?;
+    let mut unpack_progress = progress.add_child("unpack");
+    pack::Bundle::write_to_directory(
+        &mut reader,
+        Some(&pack_dir),
+        &mut unpack_progress,
+        &gix::interrupt::IS_INTERRUPTED,
+        None::<Never>,
+        pack::bundle::write::Options {
+            object_hash: repo.object_hash(),
+            ..Default::default()
+        },
+    )
+    .map_err(bundle_error)?;
+
+    let head = refs
+        .iter()
+        .find(|(_, name)| name == "HEAD")
+        .map(|(id, _)| *id);
+    let mut head_branch = None;
+    for (id, name) in refs.iter().filter(|(_, name)| name != "HEAD") {
+        repo.reference(
+            name.as_str(),
+            *id,
+            PreviousValue::MustNotExist,
+            "restore from bundle",
+        )
+        .map_err(bundle_error)?;
+        if head_branch.is_none() && head == Some(*id) {
+            head_branch = Some(name.clone());
+        }
+    }
+    if let Some(branch) = head_branch {
+        repo.edit_reference(RefEdit {
+            change: Change::Update {
+                log: LogChange::default(),
+                expected: PreviousValue::Any,
+                new: Target::Symbolic(branch.as_str().try_into().map_err(bundle_error)?),
+            },
+            name: "HEAD".try_into().map_err(bundle_error)?,
+            deref: false,
+        })
+        .map_err(bundle_error)?;
+    }
+
+    let mut checkout_progress = progress.add_child("checkout");
+    checkout(&repo, target, &mut checkout_progress)?;
+
+    init::merge_driver(&repo)
+}
+
+/// The refs listed in the header, `None` if it is not a complete v2 bundle.
+fn header(reader: &mut impl BufRead) -> Option<Vec<(ObjectId, String)>> {
+    let mut lines = reader.lines();
+    if lines.next()?.ok()? != SIGNATURE {
+        return None;
+    }
+
+    let mut refs = vec![];
+    for line in lines {
+        let line = line.ok()?;
+        if line.is_empty() {
+            return Some(refs);
+        }
+        let (id, name) = line.split_once(' ')?;
+        refs.push((ObjectId::from_hex(id.as_bytes()).ok()?, name.to_owned()));
+    }
+
+    None
+}
+
+/// Writes the files of `HEAD` into the worktree, along with the index.
+fn checkout<P>(repo: &Repository, worktree: &Path, progress: &mut P) -> Result<()>
+where
+    P: Progress,
+{
+    let Ok(head) = repo.head_id() else {
+        return Ok(());
+    };
+    let files = blobs(repo, Some(head.detach()), None).map_err(bundle_error)?;
+    progress.init(Some(files.len()), None);
+
+    for (path, id) in &files {
+        let file = worktree.join(path);
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file, &repo.find_blob(*id).map_err(bundle_error)?.data)?;
+        progress.inc();
+    }
+
+    let tree = head
+        .object()
+        .map_err(bundle_error)?
+        .peel_to_tree()
+        .map_err(bundle_error)?;
+    let mut index = repo.index_from_tree(&tree.id).map_err(bundle_error)?;
+    index.write(Default::default()).map_err(bundle_error)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+    use gix::progress::Discard;
+
+    use super::*;
+    use crate::data::{tests::init_store, Change as DataChange, Entry, Store};
+
+    async fn store_with_entries(tmp: &TempDir) -> Store {
+        let store = init_store(tmp);
+        for start in ["2026-10-19T08:00:00Z", "2026-10-20T08:00:00Z"] {
+            let entry = Entry::new(None, vec![], start.parse().unwrap());
+            store
+                .commit(
+                    "start",
+                    vec![DataChange::record(entry.path(), &entry).unwrap()],
+                )
+                .await
+                .unwrap();
+        }
+        store
+    }
+
+    #[tokio::test]
+    async fn bundles_restore_the_repository() {
+        let tmp = TempDir::new().unwrap();
+        let store = store_with_entries(&tmp).await;
+        let bundle = tmp.child(format!("repo{EXTENSION}"));
+        let target = tmp.child("restored");
+
+        let count = write(store.root(), &bundle, &mut Discard).unwrap();
+        restore(&bundle, &target, &mut Discard).unwrap();
+        let repo = gix::open(store.root()).unwrap();
+
+        // 2 commits, 2 entry blobs and 4 trees each
+        assert_eq!(12, count);
+        assert!(!backup::partial(&bundle).exists());
+        let restored = Store::new(target.path());
+        assert_eq!(
+            store.entries().await.unwrap(),
+            restored.entries().await.unwrap()
+        );
+        let restored = gix::open(target.path()).unwrap();
+        assert_eq!(repo.head_name().unwrap(), restored.head_name().unwrap());
+        assert_eq!(
+            repo.head_id().unwrap().detach(),
+            restored.head_id().unwrap().detach()
+        );
+        assert!(!restored.is_dirty().unwrap());
+    }
+
+    #[tokio::test]
+    async fn invalid_bundles_leave_nothing_behind() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("repo.bundle").write_str("not a bundle").unwrap();
+        let target = tmp.child("restored");
+
+        let result = restore(tmp.child("repo.bundle").path(), &target, &mut Discard);
+
+        assert!(matches!(result, Err(Error::InvalidBundle(_))));
+        assert!(!target.exists());
+    }
+
+    #[tokio::test]
+    async fn empty_repositories_can_not_be_bundled() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        let result = write(store.root(), tmp.child("repo.bundle").path(), &mut Discard);
+
+        assert!(matches!(result, Err(Error::EmptyRepo)));
+    }
+
+    #[tokio::test]
+    async fn failed_bundles_leave_nothing_behind() {
+        let tmp = TempDir::new().unwrap();
+        let store = store_with_entries(&tmp).await;
+        let entry = Entry::new(None, vec![], "2026-10-19T08:00:00Z".parse().unwrap());
+        let blob = gix::open(store.root())
+            .unwrap()
+            .write_blob(toml::to_string(&entry).unwrap())
+            .unwrap()
+            .to_string();
+        // the object list is complete, reading the blob fails while writing
+        fs::remove_file(
+            store
+                .root()
+                .join(".git/objects")
+                .join(&blob[..2])
+                .join(&blob[2..]),
+        )
+        .unwrap();
+        let backups = tmp.child("backups");
+        backups.create_dir_all().unwrap();
+
+        let result = write(
+            store.root(),
+            backups.child(format!("repo{EXTENSION}")).path(),
+            &mut Discard,
+        );
+
+        assert!(result.is_err());
+        assert_eq!(0, fs::read_dir(&backups).unwrap().count());
+    }
+}
@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: MIT
 
-use std::io::Error as IoError;
+use std::{io::Error as IoError, path::PathBuf};
 
 use gix::clone::checkout::main_worktree::Error as GixCheckoutError;
 use gix::clone::fetch::Error as GixFetchError;
@@ -28,8 +28,32 @@ pub enum Error {
     GixCheckout(#[from] Box<GixCheckoutError>),
     #[error("parsing '{}' into a gix-URL failed", .1)]
     GixUrlParse(#[source] UrlParseError, String),
+    #[error("'{}' is not a valid branch name", .0)]
+    InvalidBranch(String, #[source] gix::validate::reference::name::Error),
+    #[error("{} can not be used as a point in time", .0)]
+    InvalidDate(jiff::civil::Date),
     #[error("the {} operation is destructive, '--force' required", .0)]
     DestructiveOperation(String),
+    #[error("the repo at {:?} already exists", .0)]
+    RepoExists(PathBuf),
+    #[error("there is no backup {:?}", .0)]
+    UnknownBackup(PathBuf),
+    #[error("{:?} is not a complete git bundle", .0)]
+    InvalidBundle(PathBuf),
+    #[error("the repo has no commits yet")]
+    EmptyRepo,
+    #[error("{:?} is not a git repository", .0)]
+    NotARepo(PathBuf, #[source] Box<gix::open::Error>),
+    #[error("{:?} is a bare repository, the data repo needs a worktree", .0)]
+    Bare(PathBuf),
+    #[error("the remote '{}' exists already", .0)]
+    RemoteExists(String),
+    #[error("failed to write the git configuration")]
+    GixConfig(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("gix bundle operation failed")]
+    GixBundle(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("interrupted, the changes so far were rolled back")]
+    Interrupted,
     #[error("couldn't join work units")]
     JoinError(#[source] JoinError),
 }
@@ -0,0 +1,384 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Integrity checks of the data repository and consistency checks of the
+//! time data stored in it.
+
+use std::{
+    collections::BTreeSet,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use gix::ObjectId;
+
+use super::bundle;
+use crate::data::{Change, Entry, Error as DataError};
+
+/// A problem found in the repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub problem: String,
+    pub suggestion: String,
+    /// The repair made by `--fix`, if there is one.
+    pub fix: Option<Fix>,
+}
+
+/// A repair, committed on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub message: String,
+    pub changes: Vec<Change>,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n  suggestion: {}", self.problem, self.suggestion)
+    }
+}
+
+impl Issue {
+    fn new(problem: String, suggestion: impl Into<String>) -> Self {
+        Self {
+            problem,
+            suggestion: suggestion.into(),
+            fix: None,
+        }
+    }
+
+    /// An issue that `--fix` repairs by committing `entry`.
+    fn fixed_by(problem: String, message: String, entry: &Entry) -> Self {
+        let changes = match Change::record(entry.path(), entry) {
+            Ok(change) => vec![change],
+            Err(_) => return Self::new(problem, "correct the entry by hand"),
+        };
+
+        Self {
+            problem,
+            suggestion: format!("`timers repo fsck --fix --force` will {message}"),
+            fix: Some(Fix { message, changes }),
+        }
+    }
+}
+
+const RESTORE: &str = "restore the repo from a backup with `timers repo restore`";
+
+/// Checks that every ref resolves and every object reachable from them is
+/// present and has the content its id claims.
+pub fn integrity(root: &Path) -> Vec<Issue> {
+    let repo = match gix::open(root) {
+        Ok(repo) => repo,
+        Err(e) => {
+            return vec![Issue::new(
+                format!("the repo can not be opened: {e}"),
+                RESTORE,
+            )]
+        }
+    };
+
+    let mut issues = vec![];
+    let mut tips = vec![];
+
+    let platform = match repo.references() {
+        Ok(platform) => platform,
+        Err(e) => return vec![Issue::new(format!("refs can not be read: {e}"), RESTORE)],
+    };
+    let refs = match platform.all() {
+        Ok(refs) => refs,
+        Err(e) => return vec![Issue::new(format!("refs can not be read: {e}"), RESTORE)],
+    };
+    for reference in refs {
+        match reference {
+            Ok(mut reference) => match reference.peel_to_id() {
+                Ok(id) => tips.push(id.detach()),
+                Err(e) => issues.push(Issue::new(
+                    format!("ref {} is broken: {e}", reference.name().as_bstr()),
+                    RESTORE,
+                )),
+            },
+            Err(e) => issues.push(Issue::new(format!("a ref can not be read: {e}"), RESTORE)),
+        }
+    }
+
+    let objects = match bundle::objects(&repo, tips.into_iter()) {
+        Ok(objects) => objects,
+        Err(e) => {
+            issues.push(Issue::new(
+                format!("the history can not be read: {}", chain(&e)),
+                RESTORE,
+            ));
+            return issues;
+        }
+    };
+    for id in objects {
+        if let Some(problem) = verify(&repo, id) {
+            issues.push(Issue::new(problem, RESTORE));
+        }
+    }
+
+    issues
+}
+
+fn verify(repo: &gix::Repository, id: ObjectId) -> Option<String> {
+    let object = match repo.find_object(id) {
+        Ok(object) => object,
+        Err(e) => return Some(format!("object {id} is missing: {e}")),
+    };
+
+    match gix::objs::compute_hash(repo.object_hash(), object.kind, &object.data) {
+        Ok(actual) if actual == id => None,
+        _ => Some(format!("object {id} is corrupt")),
+    }
+}
+
+/// Checks the entry `files`: every file has to be readable, entries end
+/// after they start, do not overlap, at most the last one is running and
+/// only uses known `projects`. Project checks are skipped if there are no
+/// `projects`.
+pub fn consistency(
+    files: &[(PathBuf, Result<Entry, DataError>)],
+    projects: &BTreeSet<String>,
+    root: &Path,
+) -> Vec<Issue> {
+    let mut issues = vec![];
+    let mut entries = vec![];
+
+    for (file, entry) in files {
+        match entry {
+            Ok(entry) => entries.push(entry),
+            Err(e) => issues.push(Issue::new(
+                format!(
+                    "{} can not be read: {}",
+                    file.strip_prefix(root).unwrap_or(file).display(),
+                    chain(e)
+                ),
+                "correct or remove the file",
+            )),
+        }
+    }
+    entries.sort_by_key(|e| e.start);
+
+    for entry in &entries {
+        if entry.end.is_some_and(|end| end < entry.start) {
+            issues.push(Issue::new(
+                format!("entry {} ends before it starts", entry.id),
+                "correct its end time",
+            ));
+        }
+        if let Some(project) = &entry.project {
+            if !projects.is_empty() && !projects.contains(project) {
+                issues.push(Issue::new(
+                    format!("entry {} refers to unknown project {project}", entry.id),
+                    format!("add [projects.{project}] to the configuration or correct the entry"),
+                ));
+            }
+        }
+    }
+
+    for pair in entries.windows(2) {
+        let (entry, next) = (pair[0], pair[1]);
+        let fixed = Entry {
+            end: Some(next.start),
+            ..entry.clone()
+        };
+
+        match entry.end {
+            None if next.end.is_none() => issues.push(Issue::fixed_by(
+                format!("entries {} and {} are both running", entry.id, next.id),
+                format!("stop {} when {} starts", entry.id, next.id),
+                &fixed,
+            )),
+            None => issues.push(Issue::fixed_by(
+                format!(
+                    "entry {} is still running after {} started",
+                    entry.id, next.id
+                ),
+                format!("stop {} when {} starts", entry.id, next.id),
+                &fixed,
+            )),
+            Some(end) if end > next.start && end >= entry.start => issues.push(Issue::fixed_by(
+                format!("entry {} overlaps {}", entry.id, next.id),
+                format!("end {} when {} starts", entry.id, next.id),
+                &fixed,
+            )),
+            Some(_) => (),
+        }
+    }
+
+    issues
+}
+
+fn chain(e: &dyn std::error::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(e) = source {
+        message.push_str(&format!(": {e}"));
+        source = e.source();
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use assert_fs::{prelude::*, TempDir};
+    use flate2::{write::ZlibEncoder, Compression};
+
+    use super::*;
+    use crate::data::{tests::init_store, Store};
+
+    fn entry(start: &str, end: Option<&str>) -> Entry {
+        let mut entry = Entry::new(Some("time_rs".to_string()), vec![], start.parse().unwrap());
+        entry.end = end.map(|end| end.parse().unwrap());
+        entry
+    }
+
+    fn files(entries: &[Entry]) -> Vec<(PathBuf, Result<Entry, DataError>)> {
+        entries.iter().map(|e| (e.path(), Ok(e.clone()))).collect()
+    }
+
+    fn problems(issues: &[Issue]) -> Vec<&str> {
+        issues.iter().map(|i| i.problem.as_str()).collect()
+    }
+
+    #[test]
+    fn consistent_entries_have_no_issues() {
+        let entries = [
+            entry("2026-10-19T08:00:00Z", Some("2026-10-19T12:00:00Z")),
+            entry("2026-10-19T12:00:00Z", Some("2026-10-19T16:00:00Z")),
+            entry("2026-10-20T08:00:00Z", None),
+        ];
+        let projects = BTreeSet::from(["time_rs".to_string()]);
+
+        assert!(consistency(&files(&entries), &projects, Path::new("")).is_empty());
+    }
+
+    #[test]
+    fn inconsistencies_are_found() {
+        let entries = [
+            entry("2026-10-19T08:00:00Z", Some("2026-10-19T13:00:00Z")),
+            entry("2026-10-19T12:00:00Z", None),
+            entry("2026-10-20T08:00:00Z", Some("2026-10-20T07:00:00Z")),
+            entry("2026-10-21T08:00:00Z", None),
+            entry("2026-10-22T08:00:00Z", None),
+        ];
+        let projects = BTreeSet::from(["other".to_string()]);
+
+        let issues = consistency(&files(&entries), &projects, Path::new(""));
+
+        assert_eq!(
+            vec![
+                "entry 20261019T080000Z refers to unknown project time_rs",
+                "entry 20261019T120000Z refers to unknown project time_rs",
+                "entry 20261020T080000Z ends before it starts",
+                "entry 20261020T080000Z refers to unknown project time_rs",
+                "entry 20261021T080000Z refers to unknown project time_rs",
+                "entry 20261022T080000Z refers to unknown project time_rs",
+                "entry 20261019T080000Z overlaps 20261019T120000Z",
+                "entry 20261019T120000Z is still running after 20261020T080000Z started",
+                "entries 20261021T080000Z and 20261022T080000Z are both running",
+            ],
+            problems(&issues)
+        );
+        let fixes = issues.iter().filter(|i| i.fix.is_some()).count();
+        assert_eq!(3, fixes);
+    }
+
+    #[test]
+    fn fixes_end_the_earlier_entry() {
+        let entries = [
+            entry("2026-10-19T08:00:00Z", Some("2026-10-19T13:00:00Z")),
+            entry("2026-10-19T12:00:00Z", Some("2026-10-19T16:00:00Z")),
+        ];
+        let fixed = entry("2026-10-19T08:00:00Z", Some("2026-10-19T12:00:00Z"));
+
+        let issues = consistency(&files(&entries), &BTreeSet::new(), Path::new(""));
+
+        assert_eq!(
+            Some(vec![Change::record(fixed.path(), &fixed).unwrap()]),
+            issues[0].fix.as_ref().map(|f| f.changes.clone())
+        );
+    }
+
+    #[tokio::test]
+    async fn unreadable_files_are_reported() {
+        let tmp = TempDir::new().unwrap();
+        let store = Store::new(tmp.path());
+        tmp.child("entries/2026/10/broken.toml")
+            .write_str("start = 1")
+            .unwrap();
+
+        let files = store.entry_files().await.unwrap();
+        let issues = consistency(&files, &BTreeSet::new(), store.root());
+
+        assert_eq!(1, issues.len());
+        assert!(issues[0]
+            .problem
+            .starts_with("entries/2026/10/broken.toml can not be read: "));
+    }
+
+    #[tokio::test]
+    async fn healthy_repos_have_integrity() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        store
+            .commit("first", vec![Change::Write("a.toml".into(), "a".into())])
+            .await
+            .unwrap();
+
+        assert_eq!(Vec::<Issue>::new(), integrity(store.root()));
+    }
+
+    /// A repo with a single commit of `a.toml`, and the loose object file of
+    /// its blob.
+    async fn with_blob(tmp: &TempDir) -> (Store, ObjectId, PathBuf) {
+        let store = init_store(tmp);
+        store
+            .commit("first", vec![Change::Write("a.toml".into(), "a".into())])
+            .await
+            .unwrap();
+        let repo = gix::open(store.root()).unwrap();
+        let blob = repo.write_blob("a").unwrap().detach();
+        let hex = blob.to_string();
+        let object = store
+            .root()
+            .join(".git/objects")
+            .join(&hex[..2])
+            .join(&hex[2..]);
+
+        (store, blob, object)
+    }
+
+    #[tokio::test]
+    async fn corrupt_objects_are_found() {
+        let tmp = TempDir::new().unwrap();
+        let (store, blob, object) = with_blob(&tmp).await;
+        // a valid loose object, but with other content than its id claims
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(b"blob 1\0b").unwrap();
+        std::fs::remove_file(&object).unwrap();
+        std::fs::write(&object, encoder.finish().unwrap()).unwrap();
+
+        let issues = integrity(store.root());
+
+        assert_eq!(1, issues.len());
+        assert_eq!(format!("object {blob} is corrupt"), issues[0].problem);
+    }
+
+    #[tokio::test]
+    async fn missing_objects_are_found() {
+        let tmp = TempDir::new().unwrap();
+        let (store, blob, object) = with_blob(&tmp).await;
+        std::fs::remove_file(&object).unwrap();
+
+        let issues = integrity(store.root());
+
+        assert_eq!(1, issues.len());
+        assert!(issues[0]
+            .problem
+            .starts_with(&format!("object {blob} is missing: ")));
+    }
+}
@@ -0,0 +1,157 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Creating or adopting the data repository and its local git configuration.
+
+use std::{fs, path::Path};
+
+use gix::{
+    create::{Kind, Options},
+    remote::Direction,
+    Repository, ThreadSafeRepository,
+};
+
+use super::Error;
+use crate::data::layout::MERGE_DRIVER;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Name of the remote configured by `init --remote`.
+pub const REMOTE: &str = "origin";
+
+/// Command git runs to merge records, see [`crate::data::merge`].
+const MERGE_COMMAND: &str = "timers repo merge %O %A %B";
+
+fn config_error<E>(e: E) -> Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    Error::GixConfig(Box::new(e))
+}
+
+/// Creates a new repository in `folder`, or opens the existing one if
+/// `adopt`ing it, then configures the merge driver and the `remote`.
+pub fn init(folder: &Path, adopt: bool, remote: Option<gix::Url>) -> Result<Repository> {
+    let repo = match adopt {
+        true => {
+            let repo =
+                gix::open(folder).map_err(|e| Error::NotARepo(folder.to_owned(), Box::new(e)))?;
+            if repo.workdir().is_none() {
+                return Err(Error::Bare(folder.to_owned()));
+            }
+            repo
+        }
+        false => {
+            fs::create_dir_all(folder)?;
+            ThreadSafeRepository::init(
+                folder,
+                Kind::WithWorktree,
+                Options {
+                    destination_must_be_empty: true,
+                    ..Options::default()
+                },
+            )
+            .map_err(Box::new)?
+            .to_thread_local()
+        }
+    };
+
+    configure(&repo, remote)?;
+
+    Ok(repo)
+}
+
+/// Writes the merge driver to the local config of `repo`, for repositories
+/// that were cloned or restored rather than initialised. git doesn't copy
+/// the config along with the history.
+pub fn merge_driver(repo: &Repository) -> Result<()> {
+    configure(repo, None)
+}
+
+/// Writes the merge driver and the `remote` to the local config of `repo`.
+/// Fails before writing anything if the remote exists already.
+fn configure(repo: &Repository, remote: Option<gix::Url>) -> Result<()> {
+    if remote.is_some() && repo.try_find_remote(REMOTE).is_some() {
+        return Err(Error::RemoteExists(REMOTE.to_owned()));
+    }
+
+    let path = repo.git_dir().join("config");
+    let mut config =
+        gix::config::File::from_path_no_includes(path.clone(), gix::config::Source::Local)
+            .map_err(config_error)?;
+    let driver = Some(MERGE_DRIVER.into());
+    config
+        .set_raw_value_by("merge", driver, "name", "merge timers records")
+        .map_err(config_error)?;
+    config
+        .set_raw_value_by("merge", driver, "driver", MERGE_COMMAND)
+        .map_err(config_error)?;
+
+    if let Some(url) = remote {
+        let fetch = format!("+refs/heads/*:refs/remotes/{REMOTE}/*");
+        repo.remote_at(url)
+            .map_err(config_error)?
+            .with_refspecs([fetch.as_str()], Direction::Fetch)
+            .map_err(config_error)?
+            .save_as_to(REMOTE, &mut config)
+            .map_err(config_error)?;
+    }
+
+    config.write_to(&mut fs::File::create(path)?)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+
+    use super::*;
+
+    const URL: &str = "https://example.com/time.git";
+
+    #[test]
+    fn the_merge_driver_and_remote_are_configured() {
+        let tmp = TempDir::new().unwrap();
+
+        init(tmp.path(), false, Some(URL.try_into().unwrap())).unwrap();
+
+        let repo = gix::open(tmp.path()).unwrap();
+        let config = repo.config_snapshot();
+        assert_eq!(
+            Some(MERGE_COMMAND.to_string()),
+            config.string("merge.timers.driver").map(|d| d.to_string())
+        );
+        let remote = repo.find_remote(REMOTE).unwrap();
+        assert_eq!(
+            URL,
+            remote
+                .url(Direction::Fetch)
+                .unwrap()
+                .to_bstring()
+                .to_string()
+        );
+        assert_eq!(1, remote.refspecs(Direction::Fetch).len());
+    }
+
+    #[test]
+    fn adopting_requires_a_repo() {
+        let tmp = TempDir::new().unwrap();
+
+        assert!(matches!(
+            init(tmp.path(), true, None),
+            Err(Error::NotARepo(..))
+        ));
+    }
+
+    #[test]
+    fn existing_remotes_are_kept() {
+        let tmp = TempDir::new().unwrap();
+        init(tmp.path(), false, Some(URL.try_into().unwrap())).unwrap();
+
+        let result = init(tmp.path(), true, Some(URL.try_into().unwrap()));
+
+        assert!(matches!(result, Err(Error::RemoteExists(_))));
+    }
+}
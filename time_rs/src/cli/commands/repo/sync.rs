@@ -0,0 +1,106 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! The remotes `repo sync` talks to.
+
+use std::path::Path;
+
+use gix::remote::Direction;
+
+use super::Error;
+use crate::{cli::commands::Result, config::Config, data::Target};
+
+/// The remotes of the configuration, or the ones of the repository at `root`
+/// if none are configured, e.g. those set up by `repo clone` or
+/// `repo init --remote`. Tokens are resolved right away.
+pub async fn targets(config: &Config, root: &Path) -> Result<Vec<Target>> {
+    if config.remotes.is_empty() {
+        let repo = gix::open(root).map_err(|e| Error::NotARepo(root.to_owned(), Box::new(e)))?;
+        let targets = repo
+            .remote_names()
+            .iter()
+            .filter_map(|name| {
+                let remote = repo.find_remote(name.as_ref()).ok()?;
+                let url = remote.url(Direction::Push)?.to_bstring().to_string();
+
+                Some(Target {
+                    name: name.to_string(),
+                    url,
+                    token: None,
+                })
+            })
+            .collect();
+
+        return Ok(targets);
+    }
+
+    let mut targets = vec![];
+    for (name, remote) in &config.remotes {
+        let token = match remote.token {
+            Some(ref token) => {
+                let origin = config.origin(&format!("remotes.{name}.token"));
+                Some(token.resolve(&origin).await?.expose().to_owned())
+            }
+            None => None,
+        };
+        targets.push(Target {
+            name: name.clone(),
+            url: remote.url.clone(),
+            token,
+        });
+    }
+
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use figment::Figment;
+
+    use super::*;
+    use crate::cli::commands::repo::init::{init, REMOTE};
+
+    const URL: &str = "https://example.com/time.git";
+
+    #[tokio::test]
+    async fn remotes_of_the_repo_are_the_fallback() {
+        let tmp = TempDir::new().unwrap();
+        init(tmp.path(), false, Some(URL.try_into().unwrap())).unwrap();
+        let config: Config = Figment::new().try_into().unwrap();
+
+        let targets = targets(&config, tmp.path()).await.unwrap();
+
+        assert_eq!(
+            vec![Target {
+                name: REMOTE.into(),
+                url: URL.into(),
+                token: None
+            }],
+            targets
+        );
+    }
+
+    #[tokio::test]
+    async fn configured_remotes_win() {
+        let tmp = TempDir::new().unwrap();
+        init(tmp.path(), false, Some(URL.try_into().unwrap())).unwrap();
+        let config: Config = Figment::new()
+            .merge(("remotes.backup.url", "/srv/time.git"))
+            .merge(("remotes.backup.token", "secret"))
+            .try_into()
+            .unwrap();
+
+        let targets = targets(&config, tmp.path()).await.unwrap();
+
+        assert_eq!(
+            vec![Target {
+                name: "backup".into(),
+                url: "/srv/time.git".into(),
+                token: Some("secret".into())
+            }],
+            targets
+        );
+    }
+}
@@ -5,39 +5,96 @@
 use std::sync::Arc;
 
 use clap::Args;
+use jiff::Timestamp;
 use prodash::tree::Root;
 
-use crate::{cli::Cli, config::Config};
+use crate::{
+    cli::Cli,
+    config::{Config, StartDefaults},
+    data::{Change, Entry, Store},
+};
 
 use super::Command;
-use super::Result;
+use super::{Error, Result};
 
-#[derive(Debug, PartialEq, Eq, Args)]
-pub struct Start {}
+#[derive(Debug, Default, PartialEq, Eq, Args)]
+pub struct Start {
+    /// Project to track the time for
+    pub project: Option<String>,
+
+    /// Tags to attach to the entry, can be given multiple times
+    #[arg(long = "tag", short)]
+    pub tags: Vec<String>,
+
+    /// Client the work is done for
+    #[arg(long)]
+    pub client: Option<String>,
+}
 
 impl Command for Start {
-    async fn run(&self, _progress: Arc<Root>, _args: &Cli, _config: Config) -> Result<()> {
+    async fn run(&self, _progress: Arc<Root>, _args: &Cli, config: Config) -> Result<()> {
+        let store = Store::open(&config)?;
+
+        if let Some(running) = store.running().await? {
+            return Err(Error::AlreadyRunning(running.start));
+        }
+
+        let entry = self.entry(&config.start, Timestamp::now());
+        store
+            .commit(
+                format!("start {}", entry.id),
+                vec![Change::record(entry.path(), &entry)?],
+            )
+            .await?;
+
         Ok(())
     }
 }
 
+impl Start {
+    /// The entry to start, values that are not given fall back to `defaults`.
+    /// Default tags are kept in addition to the given ones.
+    fn entry(&self, defaults: &StartDefaults, start: Timestamp) -> Entry {
+        let project = self.project.clone().or_else(|| defaults.project.clone());
+        let mut tags = defaults.tags.clone();
+        tags.extend(
+            self.tags
+                .iter()
+                .filter(|t| !defaults.tags.contains(t))
+                .cloned(),
+        );
+
+        Entry {
+            client: self.client.clone().or_else(|| defaults.client.clone()),
+            ..Entry::new(project, tags, start)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
     use figment::Figment;
     use prodash::tree::root::Options;
+    use rstest::*;
 
-    use crate::cli::Commands;
+    use crate::{cli::Commands, data::tests::init_store};
 
     use super::*;
 
     #[tokio::test]
     async fn basic_operation_succeeds() -> eyre::Result<()> {
-        let start = Start {};
-        let figment = Figment::new().merge(("data_dir", "/"));
+        let tmp = assert_fs::TempDir::new()?;
+        let store = init_store(&tmp);
+        let start = Start {
+            project: Some("time_rs".to_string()),
+            tags: vec!["dev".to_string()],
+            client: None,
+        };
+        let figment = Figment::new().merge(("data_dir", tmp.path()));
         let cli_args = Cli {
-            command: Some(Commands::Start(Start {})),
+            command: Some(Commands::Start(Start::default())),
             ..Default::default()
         };
         let config = figment.try_into()?;
@@ -46,6 +103,60 @@ mod tests {
         let result = start.run(progress, &cli_args, config).await;
 
         assert!(result.is_ok());
+        let running = store.running().await?.unwrap();
+        assert_eq!(Some("time_rs".to_string()), running.project);
+        assert_eq!(vec!["dev".to_string()], running.tags);
+
+        Ok(())
+    }
+
+    #[rstest]
+    #[case::defaults(Start::default(), Some("time_rs"), &["local"], Some("acme"))]
+    #[case::given(
+        Start {
+            project: Some("other".to_string()),
+            tags: vec!["dev".to_string(), "local".to_string()],
+            client: Some("initech".to_string()),
+        },
+        Some("other"),
+        &["local", "dev"],
+        Some("initech"),
+    )]
+    fn defaults_fill_missing_values(
+        #[case] start: Start,
+        #[case] project: Option<&str>,
+        #[case] tags: &[&str],
+        #[case] client: Option<&str>,
+    ) {
+        let defaults = StartDefaults {
+            project: Some("time_rs".to_string()),
+            tags: vec!["local".to_string()],
+            client: Some("acme".to_string()),
+        };
+
+        let entry = start.entry(&defaults, Timestamp::now());
+
+        assert_eq!(project, entry.project.as_deref());
+        assert_eq!(tags, entry.tags);
+        assert_eq!(client, entry.client.as_deref());
+    }
+
+    #[tokio::test]
+    async fn fails_when_already_running() -> eyre::Result<()> {
+        let tmp = assert_fs::TempDir::new()?;
+        init_store(&tmp);
+        let figment = Figment::new().merge(("data_dir", tmp.path()));
+        let cli_args = Cli::default();
+        let progress: Arc<_> = Options::default().create().into();
+
+        Start::default()
+            .run(progress.clone(), &cli_args, figment.clone().try_into()?)
+            .await?;
+        let result = Start::default()
+            .run(progress, &cli_args, figment.try_into()?)
+            .await;
+
+        assert!(matches!(result, Err(Error::AlreadyRunning(_))));
 
         Ok(())
     }
@@ -5,40 +5,138 @@
 use std::sync::Arc;
 
 use clap::Args;
+use jiff::{tz::TimeZone, Zoned};
 use prodash::tree::Root;
 
-use crate::{cli::Cli, config::Config};
+use crate::{
+    cli::Cli,
+    config::Config,
+    data::{Entry, Store, SyncState},
+};
 
+use super::budget;
 use super::Command;
-use super::Result;
+use super::{format_duration, Result};
 
 #[derive(Debug, PartialEq, Eq, Args)]
 pub struct Status {}
 
 impl Command for Status {
-    async fn run(&self, _progress: Arc<Root>, _args: &Cli, _config: Config) -> Result<()> {
+    async fn run(&self, _progress: Arc<Root>, _args: &Cli, config: Config) -> Result<()> {
+        let store = Store::open(&config)?;
+
+        if let Some(ref data_dir) = config.data_dir {
+            let failures = SyncState::load(data_dir).await?.failures;
+            for failure in &failures {
+                let at = failure.at.to_zoned(TimeZone::system()).strftime("%F %R");
+                eprintln!("warning: background sync at {at} failed: {}", failure.error);
+            }
+            if !failures.is_empty() {
+                eprintln!("warning: run `timers repo sync` to retry");
+            }
+        }
+
+        let Some(running) = store.running().await? else {
+            println!("no timer running");
+            return Ok(());
+        };
+
+        let now = Zoned::now();
+        println!(
+            "{} running since {} ({})",
+            running.project.as_deref().unwrap_or("timer"),
+            running
+                .start
+                .to_zoned(now.time_zone().clone())
+                .strftime("%F %R"),
+            format_duration(running.duration(now.timestamp()), false),
+        );
+
+        for warning in warnings(&config, &store, &running, &now).await? {
+            eprintln!("warning: {warning}");
+        }
+
         Ok(())
     }
 }
 
+/// The budget warnings of the project of the `running` entry.
+async fn warnings(
+    config: &Config,
+    store: &Store,
+    running: &Entry,
+    now: &Zoned,
+) -> Result<Vec<String>> {
+    let Some(ref project) = running.project else {
+        return Ok(vec![]);
+    };
+    let limits = budget::limits(config, store).await?;
+    let entries = store.entries().await?;
+
+    Ok(budget::usages(&limits, &entries, now)
+        .iter()
+        .filter(|u| u.project == *project)
+        .filter_map(budget::Usage::warning)
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::cli::commands::Command;
     use crate::cli::Cli;
     use crate::config::Config;
+    use crate::data::{tests::init_store, Change};
+    use figment::Figment;
+    use jiff::SignedDuration;
     use prodash::tree::Root;
     use std::sync::Arc;
 
     #[tokio::test]
     async fn test_status_run() {
         let temp = assert_fs::TempDir::new().unwrap();
-        let home_dir = temp.path().to_path_buf();
         let cli = Cli::default();
-        let config = Config::load(vec![home_dir]).await.unwrap();
+        let config: Config = Figment::new()
+            .merge(("data_dir", temp.path()))
+            .try_into()
+            .unwrap();
         let progress = Arc::new(Root::new());
         let status = Status {};
         let result = status.run(Arc::clone(&progress), &cli, config).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_status_with_exceeded_budget() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let store = init_store(&temp);
+        let now = Zoned::now();
+        let entry = Entry::new(
+            Some("time_rs".to_string()),
+            vec![],
+            now.timestamp() - SignedDuration::from_mins(30),
+        );
+        store
+            .commit("start", vec![Change::record(entry.path(), &entry).unwrap()])
+            .await
+            .unwrap();
+        let config = || -> Config {
+            Figment::new()
+                .merge(("data_dir", temp.path()))
+                .merge(("projects.time_rs.budget.total", 0))
+                .try_into()
+                .unwrap()
+        };
+        let cli = Cli::default();
+        let progress = Arc::new(Root::new());
+        let status = Status {};
+        let result = status.run(Arc::clone(&progress), &cli, config()).await;
+        assert!(result.is_ok());
+
+        let warnings = warnings(&config(), &store, &entry, &now).await.unwrap();
+        assert_eq!(
+            vec!["total budget of time_rs exceeded: 0:30 of 0:00 used"],
+            warnings
+        );
+    }
 }
@@ -5,40 +5,173 @@
 use std::sync::Arc;
 
 use clap::Args;
+use jiff::{tz::TimeZone, Timestamp};
 use prodash::tree::Root;
 
-use crate::{cli::Cli, config::Config};
+use crate::{
+    cli::Cli,
+    config::Config,
+    data::{Change, Entry, Store},
+};
 
+use super::check;
 use super::Command;
-use super::Result;
+use super::{Error, Result};
 
 #[derive(Debug, PartialEq, Eq, Args)]
 pub struct Stop {}
 
 impl Command for Stop {
-    async fn run(&self, _progress: Arc<Root>, _args: &Cli, _config: Config) -> Result<()> {
+    async fn run(&self, _progress: Arc<Root>, _args: &Cli, config: Config) -> Result<()> {
+        let store = Store::open(&config)?;
+
+        let running = store.running().await?.ok_or(Error::NotRunning)?;
+        let now = Timestamp::now();
+        let entry = Entry {
+            end: Some(now),
+            ..running
+        };
+
+        store
+            .commit(
+                format!("stop {}", entry.id),
+                vec![Change::record(entry.path(), &entry)?],
+            )
+            .await?;
+
+        let tz = TimeZone::system();
+        for warning in warnings(&config, &store, &entry, &tz, now).await? {
+            eprintln!("warning: {warning}");
+        }
+
         Ok(())
     }
 }
 
+/// The compliance violations on the day `entry` started, if the check on
+/// stop is enabled.
+async fn warnings(
+    config: &Config,
+    store: &Store,
+    entry: &Entry,
+    tz: &TimeZone,
+    now: Timestamp,
+) -> Result<Vec<String>> {
+    if !config.compliance.on_stop {
+        return Ok(vec![]);
+    }
+
+    let day = entry.start.to_zoned(tz.clone()).date();
+    let entries = store.entries().await?;
+
+    Ok(check::evaluate(
+        &config.compliance.rules,
+        &entries,
+        Some(day),
+        Some(day),
+        tz,
+        now,
+    )
+    .iter()
+    .map(ToString::to_string)
+    .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cli::commands::Command;
+    use crate::cli::commands::{format_duration, Command};
     use crate::cli::Cli;
     use crate::config::Config;
+    use crate::data::tests::init_store;
+    use figment::Figment;
     use prodash::tree::Root;
+    use rstest::rstest;
     use std::sync::Arc;
 
     #[tokio::test]
     async fn test_stop_run() {
         let temp = assert_fs::TempDir::new().unwrap();
-        let home_dir = temp.path().to_path_buf();
+        let store = init_store(&temp);
+        let entry = Entry::new(None, vec![], Timestamp::now());
+        store
+            .commit("start", vec![Change::record(entry.path(), &entry).unwrap()])
+            .await
+            .unwrap();
         let cli = Cli::default();
-        let config = Config::load(vec![home_dir]).await.unwrap();
+        let config: Config = Figment::new()
+            .merge(("data_dir", temp.path()))
+            .try_into()
+            .unwrap();
         let progress = Arc::new(Root::new());
         let stop = Stop {};
         let result = stop.run(Arc::clone(&progress), &cli, config).await;
         assert!(result.is_ok());
+        assert_eq!(None, store.running().await.unwrap());
+        assert!(store.entries().await.unwrap()[0].end.is_some());
+    }
+
+    fn hook_config(temp: &assert_fs::TempDir, on_stop: bool) -> Config {
+        Figment::new()
+            .merge(("data_dir", temp.path()))
+            .merge(("compliance.on_stop", on_stop))
+            .merge(("compliance.rules.strict.max_daily_hours", 1))
+            .try_into()
+            .unwrap()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_stop_with_compliance_hook(#[values(true, false)] on_stop: bool) {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let store = init_store(&temp);
+        let entry = Entry::new(None, vec![], "2026-10-12T06:00:00Z".parse().unwrap());
+        store
+            .commit("start", vec![Change::record(entry.path(), &entry).unwrap()])
+            .await
+            .unwrap();
+        let cli = Cli::default();
+        let progress = Arc::new(Root::new());
+        let stop = Stop {};
+        let result = stop
+            .run(Arc::clone(&progress), &cli, hook_config(&temp, on_stop))
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(None, store.running().await.unwrap());
+
+        let stopped = store.entries().await.unwrap().remove(0);
+        let end = stopped.end.unwrap();
+        let warnings = warnings(
+            &hook_config(&temp, on_stop),
+            &store,
+            &stopped,
+            &TimeZone::UTC,
+            end,
+        )
+        .await
+        .unwrap();
+        let expected = format!(
+            "2026-10-12 strict/max_daily_hours: worked {}, at most 1:00 allowed",
+            format_duration(end.duration_since(entry.start), false)
+        );
+        match on_stop {
+            true => assert_eq!(vec![expected], warnings),
+            false => assert!(warnings.is_empty()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stop_without_running_timer() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        init_store(&temp);
+        let cli = Cli::default();
+        let config: Config = Figment::new()
+            .merge(("data_dir", temp.path()))
+            .try_into()
+            .unwrap();
+        let progress = Arc::new(Root::new());
+        let stop = Stop {};
+        let result = stop.run(Arc::clone(&progress), &cli, config).await;
+        assert!(matches!(result, Err(Error::NotRunning)));
     }
 }
@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::sync::Arc;
+
+use clap::Args;
+use jiff::tz::TimeZone;
+use prodash::tree::Root;
+
+use crate::{cli::Cli, config::Config, data::Store};
+
+use super::{history, Command, Result};
+
+#[derive(Debug, PartialEq, Eq, Args)]
+pub struct Undo {
+    /// Number of changes to revert
+    #[arg(default_value_t = 1)]
+    pub count: usize,
+}
+
+impl Command for Undo {
+    async fn run(&self, _progress: Arc<Root>, _args: &Cli, config: Config) -> Result<()> {
+        let store = Store::open(&config)?;
+
+        let created = store.undo(self.count).await?;
+        print_created(&store, created.len()).await
+    }
+}
+
+/// Shows the `count` commits that were just created.
+pub(crate) async fn print_created(store: &Store, count: usize) -> Result<()> {
+    let mut revisions = store.history(None).await?;
+    revisions.truncate(count);
+    print!("{}", history::render(&revisions, &TimeZone::system()));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use figment::Figment;
+    use jiff::Timestamp;
+
+    use super::*;
+    use crate::cli::commands::Error;
+    use crate::data::{tests::init_store, Change, Entry, Error as DataError};
+
+    #[tokio::test]
+    async fn test_undo_run() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let store = init_store(&temp);
+        let entry = Entry::new(None, vec![], Timestamp::now());
+        store
+            .commit("start", vec![Change::record(entry.path(), &entry).unwrap()])
+            .await
+            .unwrap();
+        let config: Config = Figment::new()
+            .merge(("data_dir", temp.path()))
+            .try_into()
+            .unwrap();
+        let undo = Undo { count: 1 };
+
+        let result = undo.run(Root::new(), &Cli::default(), config).await;
+
+        assert!(result.is_ok());
+        assert!(store.entries().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_undo_without_changes() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        init_store(&temp);
+        let config: Config = Figment::new()
+            .merge(("data_dir", temp.path()))
+            .try_into()
+            .unwrap();
+        let undo = Undo { count: 1 };
+
+        let result = undo.run(Root::new(), &Cli::default(), config).await;
+
+        assert!(matches!(result, Err(Error::Data(DataError::NothingToUndo))));
+    }
+}
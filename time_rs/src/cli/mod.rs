@@ -5,13 +5,23 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
+use jiff::{SignedDuration, Timestamp};
+
+use crate::{
+    config::{AutoSync, SyncSettings},
+    data::SyncState,
+};
 
 pub mod commands;
 
 #[derive(Parser, Debug, Default)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
-    /// Basefolder to use for data, can be influenced by `XDG_DATA_HOME`.
+    /// Basefolder to use for data.
+    ///
+    /// Wins over `TIMERS_DATA_DIR`, which wins over `data_dir` in the
+    /// configuration files. Only without any of them the data lives in
+    /// `$XDG_DATA_HOME/timers`.
     #[arg(long, short, global = true)]
     pub data_dir: Option<PathBuf>,
 
@@ -19,10 +29,19 @@ pub struct Cli {
     #[arg(long, short, global = true)]
     pub config_dir: Option<PathBuf>,
 
+    /// Configuration profile to use instead of `default`.
+    #[arg(long, short, global = true, env = "TIMERS_PROFILE")]
+    pub profile: Option<String>,
+
     /// Enforces a given operation, even if it is destructive
     #[arg(long, global = true, default_value_t = false)]
     pub force: bool,
 
+    /// Fail right away if another timers process is changing the data,
+    /// instead of waiting for it.
+    #[arg(long, global = true, default_value_t = false)]
+    pub no_wait: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -37,10 +56,78 @@ pub enum Commands {
     Status(commands::Status),
     /// Prints the summary of a given time frame
     Summary(commands::Summary),
+    /// Compares worked time against the targets and shows the flex time balance
+    Balance(commands::Balance),
+    /// Checks the recorded time against the configured labor-law rules
+    Check(commands::Check),
+    /// Shows consumed and remaining project budgets
+    Budget(commands::Budget),
+    /// Manage vacation, sick days and other absences
+    Absence(commands::Absence),
+    /// Shows who changed which entries and when
+    History(commands::History),
+    /// Reverts the last changes to the time data
+    Undo(commands::Undo),
+    /// Reapplies changes reverted by `undo`
+    Redo(commands::Redo),
+    /// Inspect the configuration
+    Config(commands::Configuration),
     /// Do operations on the underlying repository
     Repo(commands::Repo),
 }
 
+impl Commands {
+    /// Whether the command has to run even if the configuration fails to load.
+    pub fn tolerates_invalid_config(&self) -> bool {
+        matches!(self, Commands::Config(config) if config.tolerates_invalid_config())
+    }
+
+    /// Whether the command works on the time data, whose format has to be
+    /// checked and migrated first.
+    pub fn uses_data(&self) -> bool {
+        match self {
+            Commands::Config(_) => false,
+            Commands::Repo(repo) => repo.uses_data(),
+            _ => true,
+        }
+    }
+
+    /// Whether the command notices Ctrl-C and rolls back, instead of being
+    /// terminated right away.
+    pub fn handles_interrupts(&self) -> bool {
+        matches!(self, Commands::Repo(repo) if repo.handles_interrupts())
+    }
+
+    /// Whether the command changes the data dir, which requires holding its
+    /// lock. Read-only commands run without it.
+    pub fn mutates(&self) -> bool {
+        match self {
+            Commands::Start(_) | Commands::Stop(_) | Commands::Undo(_) | Commands::Redo(_) => true,
+            Commands::Absence(absence) => absence.mutates(),
+            Commands::Repo(repo) => repo.mutates(),
+            _ => false,
+        }
+    }
+
+    /// Whether to sync in the background after the command succeeded `now`.
+    /// Repo and config commands never trigger a sync.
+    pub fn syncs_after(&self, settings: &SyncSettings, state: &SyncState, now: Timestamp) -> bool {
+        if matches!(self, Commands::Repo(_) | Commands::Config(_)) {
+            return false;
+        }
+
+        match settings.auto {
+            AutoSync::AfterWrite => self.mutates(),
+            AutoSync::Interval => state.attempted.is_none_or(|at| {
+                let interval =
+                    SignedDuration::try_from(settings.interval()).unwrap_or(SignedDuration::MAX);
+                now.duration_since(at) >= interval
+            }),
+            AutoSync::Off => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use clap::Parser;
@@ -48,12 +135,15 @@ mod tests {
     use rstest::*;
 
     use super::Cli;
+    use crate::config::{AutoSync, SyncSettings};
+    use crate::data::SyncState;
+    use jiff::{SignedDuration, Timestamp};
 
     use super::commands::{Start, Status, Stop, Summary};
     use super::Commands;
 
     #[rstest]
-    #[case(Commands::Start(Start {}), "start")]
+    #[case(Commands::Start(Start::default()), "start")]
     #[case(Commands::Stop(Stop {}), "stop")]
     #[case(Commands::Status(Status {}), "status")]
     #[case(Commands::Summary(Summary {}), "summary")]
@@ -62,4 +152,116 @@ mod tests {
 
         assert_eq!(cmd, cli.command.unwrap());
     }
+
+    #[test]
+    fn profile_is_global() {
+        let cli = Cli::parse_from(["timers", "status", "--profile", "freelance"]);
+
+        assert_eq!(Some("freelance".to_string()), cli.profile);
+    }
+
+    #[rstest]
+    #[case(&["timers", "config", "check"], true)]
+    #[case(&["timers", "config", "schema"], true)]
+    #[case(&["timers", "config", "set", "data_dir", "/tmp"], true)]
+    #[case(&["timers", "config", "unset", "data_dir"], true)]
+    #[case(&["timers", "config", "show"], false)]
+    #[case(&["timers", "status"], false)]
+    fn config_inspection_and_repair_tolerate_invalid_config(
+        #[case] args: &[&str],
+        #[case] expected: bool,
+    ) {
+        let cli = Cli::parse_from(args);
+
+        assert_eq!(expected, cli.command.unwrap().tolerates_invalid_config());
+    }
+
+    #[rstest]
+    #[case(&["timers", "status"], true)]
+    #[case(&["timers", "repo", "fsck"], true)]
+    #[case(&["timers", "repo", "init"], false)]
+    #[case(&["timers", "repo", "restore"], false)]
+    #[case(&["timers", "config", "show"], false)]
+    fn data_commands_are_detected(#[case] args: &[&str], #[case] expected: bool) {
+        let cli = Cli::parse_from(args);
+
+        assert_eq!(expected, cli.command.unwrap().uses_data());
+    }
+
+    #[rstest]
+    #[case(&["timers", "start"], true)]
+    #[case(&["timers", "stop"], true)]
+    #[case(&["timers", "undo"], true)]
+    #[case(&["timers", "absence", "remove", "x"], true)]
+    #[case(&["timers", "absence", "list"], false)]
+    #[case(&["timers", "repo", "init"], true)]
+    #[case(&["timers", "repo", "fsck", "--fix"], true)]
+    #[case(&["timers", "repo", "fsck"], false)]
+    #[case(&["timers", "repo", "backup"], false)]
+    #[case(&["timers", "repo", "destroy", "--dry-run"], false)]
+    #[case(&["timers", "repo", "restore"], false)]
+    #[case(&["timers", "status"], false)]
+    #[case(&["timers", "history"], false)]
+    #[case(&["timers", "config", "show"], false)]
+    fn mutating_commands_are_detected(#[case] args: &[&str], #[case] expected: bool) {
+        let cli = Cli::parse_from(args);
+
+        assert_eq!(expected, cli.command.unwrap().mutates());
+    }
+
+    #[rstest]
+    #[case(&["timers", "repo", "clone", "x"], true)]
+    #[case(&["timers", "repo", "sync"], true)]
+    #[case(&["timers", "repo", "restore"], true)]
+    #[case(&["timers", "repo", "fsck"], false)]
+    #[case(&["timers", "start"], false)]
+    fn interrupt_aware_commands_are_detected(#[case] args: &[&str], #[case] expected: bool) {
+        let cli = Cli::parse_from(args);
+
+        assert_eq!(expected, cli.command.unwrap().handles_interrupts());
+    }
+
+    #[rstest]
+    #[case(AutoSync::AfterWrite, &["timers", "start"], true)]
+    #[case(AutoSync::AfterWrite, &["timers", "status"], false)]
+    #[case(AutoSync::AfterWrite, &["timers", "repo", "init"], false)]
+    #[case(AutoSync::Interval, &["timers", "status"], true)]
+    #[case(AutoSync::Interval, &["timers", "repo", "sync"], false)]
+    #[case(AutoSync::Off, &["timers", "start"], false)]
+    fn syncs_follow_the_auto_setting(
+        #[case] auto: AutoSync,
+        #[case] args: &[&str],
+        #[case] expected: bool,
+    ) {
+        let cli = Cli::parse_from(args);
+        let settings = SyncSettings {
+            auto,
+            ..SyncSettings::default()
+        };
+
+        assert_eq!(
+            expected,
+            cli.command
+                .unwrap()
+                .syncs_after(&settings, &SyncState::default(), Timestamp::now())
+        );
+    }
+
+    #[test]
+    fn interval_syncs_wait_for_the_interval() {
+        let cli = Cli::parse_from(["timers", "status"]);
+        let command = cli.command.unwrap();
+        let settings = SyncSettings {
+            auto: AutoSync::Interval,
+            interval: 15,
+        };
+        let now = Timestamp::now();
+        let mut state = SyncState::default();
+
+        state.attempt(now - SignedDuration::from_mins(10));
+        assert!(!command.syncs_after(&settings, &state, now));
+
+        state.attempt(now - SignedDuration::from_mins(15));
+        assert!(command.syncs_after(&settings, &state, now));
+    }
 }
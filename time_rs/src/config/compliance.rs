@@ -0,0 +1,38 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Labor-law rules that recorded time is checked against.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, JsonSchema)]
+pub struct Compliance {
+    /// Check the stopped day after every `stop`.
+    #[serde(default)]
+    pub on_stop: bool,
+    /// Named rule sets, all of them are evaluated.
+    #[serde(default)]
+    pub rules: BTreeMap<String, RuleSet>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, JsonSchema)]
+pub struct RuleSet {
+    /// Maximum working time per day.
+    pub max_daily_hours: Option<f64>,
+    /// Minimum rest between two working days.
+    pub min_rest_hours: Option<f64>,
+    /// Breaks that are required once a day exceeds a working time.
+    #[serde(default)]
+    pub breaks: Vec<BreakRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
+pub struct BreakRule {
+    /// Working time that has to be exceeded for the rule to apply.
+    pub after_hours: f64,
+    /// Minimum total break in minutes.
+    pub minutes: u32,
+}
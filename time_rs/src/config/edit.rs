@@ -0,0 +1,500 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use figment::{
+    value::{Dict, Value},
+    Profile, Provider,
+};
+use serde_json::Value as Json;
+use tokio::task;
+use toml_edit::{DocumentMut, InlineTable, Item, Table, TableLike};
+
+use super::{discover, file_figment, is_supported, local::LOCAL_DIR, Error, Result};
+
+/// File that is created in the user config dir if none exists yet.
+pub const DEFAULT_FILE: &str = "config.toml";
+
+enum Op {
+    Set(Json),
+    Unset,
+}
+
+/// Files in the user dir `dir` that set `key` in `profile`, in load order.
+/// Files that fail to load are skipped.
+pub async fn defining(dir: PathBuf, profile: Profile, key: String) -> Result<Vec<PathBuf>> {
+    task::spawn_blocking(move || {
+        let path = key_path(&profile, &key);
+
+        Ok(discover(std::slice::from_ref(&dir))?
+            .into_iter()
+            .filter(|f| is_supported(f))
+            .filter(|f| {
+                file_figment(f.to_owned(), true)
+                    .ok()
+                    .and_then(|figment| figment.data().ok())
+                    .and_then(|mut data| data.remove(&profile))
+                    .is_some_and(|dict| defines(&dict, &path[1..]))
+            })
+            .collect())
+    })
+    .await
+    .map_err(Error::JoinError)?
+}
+
+/// The user dir, the only one that gets edited. It is the last of `dirs`
+/// that is not project local, system dirs come before it.
+pub fn user_dir(dirs: &[PathBuf]) -> Option<&Path> {
+    dirs.iter()
+        .rev()
+        .filter(|d| !d.is_file())
+        .find(|d| d.file_name().is_none_or(|name| name != LOCAL_DIR))
+        .map(PathBuf::as_path)
+}
+
+/// `file` resolved against the user dir `dir`, which it has to be inside
+/// of.
+pub fn user_file(dir: &Path, file: &Path) -> Result<PathBuf> {
+    let file = dir.join(file);
+
+    match file.starts_with(dir) && !file.components().any(|c| c == Component::ParentDir) {
+        true => Ok(file),
+        false => Err(Error::NotUserConfig(file, dir.to_owned())),
+    }
+}
+
+/// The file that is loaded last from the user dir, or a new [`DEFAULT_FILE`]
+/// in it.
+pub fn default_file(dirs: &[PathBuf]) -> Option<PathBuf> {
+    let dir = user_dir(dirs)?;
+
+    let last = discover(&[dir.to_owned()])
+        .ok()
+        .and_then(|files| files.into_iter().rfind(|f| is_supported(f)));
+
+    Some(last.unwrap_or_else(|| dir.join(DEFAULT_FILE)))
+}
+
+/// Sets `key` of `profile` in `file`, comments and layout of TOML files are
+/// kept, YAML and JSON files are rewritten without comments and with sorted
+/// keys.
+pub async fn set(file: PathBuf, profile: Profile, key: String, value: Value) -> Result<()> {
+    let value = serde_json::to_value(&value).map_err(Error::Serialize)?;
+
+    task::spawn_blocking(move || apply(&file, &key_path(&profile, &key), Op::Set(value)))
+        .await
+        .map_err(Error::JoinError)?
+        .map(|_| ())
+}
+
+/// Removes `key` of `profile` from `file`, `false` if it was not set there.
+pub async fn unset(file: PathBuf, profile: Profile, key: String) -> Result<bool> {
+    task::spawn_blocking(move || apply(&file, &key_path(&profile, &key), Op::Unset))
+        .await
+        .map_err(Error::JoinError)?
+}
+
+fn key_path(profile: &Profile, key: &str) -> Vec<String> {
+    std::iter::once(profile.as_str().to_string())
+        .chain(key.split('.').map(str::to_string))
+        .collect()
+}
+
+fn defines(dict: &Dict, path: &[String]) -> bool {
+    match path {
+        [] => false,
+        [key] => dict.contains_key(key),
+        [key, rest @ ..] => {
+            matches!(dict.get(key), Some(Value::Dict(_, dict)) if defines(dict, rest))
+        }
+    }
+}
+
+fn apply(file: &Path, path: &[String], op: Op) -> Result<bool> {
+    let text = match fs::read_to_string(file) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(Error::ReadFile(file.to_owned(), e)),
+    };
+
+    let (text, changed) = match file.extension().and_then(|s| s.to_str()) {
+        Some("toml") => {
+            let mut doc = text
+                .parse::<DocumentMut>()
+                .map_err(|e| Error::Toml(file.to_owned(), e))?;
+            let changed = edit_toml(doc.as_table_mut(), path, 0, &op)?;
+            (doc.to_string(), changed)
+        }
+        Some("yaml" | "yml") => {
+            let mut tree = serde_yaml::from_str::<Option<Json>>(&text)
+                .map_err(|e| Error::Yaml(file.to_owned(), e))?
+                .unwrap_or_else(|| Json::Object(Default::default()));
+            let changed = edit_tree(&mut tree, path, 0, &op)?;
+            let text = serde_yaml::to_string(&tree).map_err(|e| Error::Yaml(file.to_owned(), e))?;
+            (text, changed)
+        }
+        Some("json") => {
+            let mut tree = match text.trim().is_empty() {
+                true => Json::Object(Default::default()),
+                false => {
+                    serde_json::from_str(&text).map_err(|e| Error::Json(file.to_owned(), e))?
+                }
+            };
+            let changed = edit_tree(&mut tree, path, 0, &op)?;
+            let text =
+                serde_json::to_string_pretty(&tree).map_err(|e| Error::Json(file.to_owned(), e))?;
+            (text + "\n", changed)
+        }
+        Some(ext) => return Err(Error::UnknownExtension(file.to_owned(), ext.to_owned())),
+        None => return Err(Error::NoExtension(file.to_owned())),
+    };
+
+    if changed {
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent).map_err(|e| Error::WriteFile(file.to_owned(), e))?;
+        }
+        fs::write(file, text).map_err(|e| Error::WriteFile(file.to_owned(), e))?;
+    }
+
+    Ok(changed)
+}
+
+/// Tables that only hold other tables don't get a header of their own.
+fn implicit_table() -> Item {
+    let mut table = Table::new();
+    table.set_implicit(true);
+
+    Item::Table(table)
+}
+
+/// Applies `op` to `path[at..]` below `table`, tables that become empty by
+/// removing a key are removed as well.
+fn edit_toml(table: &mut dyn TableLike, path: &[String], at: usize, op: &Op) -> Result<bool> {
+    let Some(key) = path.get(at) else {
+        return Ok(false);
+    };
+
+    if at + 1 == path.len() {
+        return match op {
+            Op::Set(value) => {
+                let value =
+                    toml_value(value).ok_or_else(|| Error::Unrepresentable(path.join(".")))?;
+                table.insert(key, Item::Value(value));
+                Ok(true)
+            }
+            Op::Unset => Ok(table.remove(key).is_some()),
+        };
+    }
+
+    if matches!(op, Op::Unset) && !table.contains_key(key) {
+        return Ok(false);
+    }
+
+    let child = table
+        .entry(key)
+        .or_insert_with(implicit_table)
+        .as_table_like_mut()
+        .ok_or_else(|| Error::NotATable(path[..=at].join(".")))?;
+    let changed = edit_toml(child, path, at + 1, op)?;
+
+    if matches!(op, Op::Unset) && child.is_empty() {
+        table.remove(key);
+    }
+
+    Ok(changed)
+}
+
+fn toml_value(value: &Json) -> Option<toml_edit::Value> {
+    Some(match value {
+        Json::Null => return None,
+        Json::Bool(b) => (*b).into(),
+        Json::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64()?.into(),
+        },
+        Json::String(s) => s.as_str().into(),
+        Json::Array(items) => items
+            .iter()
+            .map(toml_value)
+            .collect::<Option<toml_edit::Array>>()?
+            .into(),
+        Json::Object(map) => map
+            .iter()
+            .map(|(k, v)| Some((k.as_str(), toml_value(v)?)))
+            .collect::<Option<InlineTable>>()?
+            .into(),
+    })
+}
+
+/// Same as [`edit_toml`], for YAML and JSON documents.
+fn edit_tree(node: &mut Json, path: &[String], at: usize, op: &Op) -> Result<bool> {
+    let Some(key) = path.get(at) else {
+        return Ok(false);
+    };
+    let map = node
+        .as_object_mut()
+        .ok_or_else(|| Error::NotATable(path[..at].join(".")))?;
+
+    if at + 1 == path.len() {
+        return match op {
+            Op::Set(value) => {
+                map.insert(key.clone(), value.clone());
+                Ok(true)
+            }
+            Op::Unset => Ok(map.remove(key).is_some()),
+        };
+    }
+
+    if matches!(op, Op::Unset) && !map.contains_key(key) {
+        return Ok(false);
+    }
+
+    let child = map
+        .entry(key.clone())
+        .or_insert_with(|| Json::Object(Default::default()));
+    let changed = edit_tree(child, path, at + 1, op)?;
+
+    if matches!(op, Op::Unset) && child.as_object().is_some_and(|m| m.is_empty()) {
+        map.remove(key);
+    }
+
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+    use rstest::*;
+
+    use super::*;
+
+    const TOML: &str = r#"# my settings
+[default]
+data_dir = "/tmp" # keep me
+
+[default.work]
+weekly_hours = 40
+"#;
+
+    async fn set_str(file: &Path, key: &str, value: &str) -> Result<()> {
+        set(
+            file.to_owned(),
+            Profile::Default,
+            key.to_string(),
+            value.parse().unwrap(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn toml_keeps_comments() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.child("config.toml");
+        file.write_str(TOML).unwrap();
+
+        set_str(&file, "work.weekly_hours", "32").await.unwrap();
+
+        let text = fs::read_to_string(&file).unwrap();
+        assert!(text.starts_with("# my settings\n"));
+        assert!(text.contains("data_dir = \"/tmp\" # keep me"));
+        assert!(text.contains("weekly_hours = 32"));
+    }
+
+    #[tokio::test]
+    async fn toml_creates_missing_tables() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.child("config.toml");
+
+        set_str(&file, "remotes.origin.url", "https://example.com/time.git")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            "[default.remotes.origin]\nurl = \"https://example.com/time.git\"\n",
+            fs::read_to_string(&file).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case("config.yaml", "default:\n  work:\n    weekly_hours: 40\n")]
+    #[case("config.json", "{\"default\": {\"work\": {\"weekly_hours\": 40}}}")]
+    #[case("config.toml", TOML)]
+    #[tokio::test]
+    async fn values_round_trip(#[case] name: &str, #[case] content: &str) {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.child(name);
+        file.write_str(content).unwrap();
+
+        set_str(&file, "work.vacation_days", "30").await.unwrap();
+        let removed = unset(
+            file.to_path_buf(),
+            Profile::Default,
+            "work.weekly_hours".to_string(),
+        )
+        .await
+        .unwrap();
+
+        let data = file_figment(file.to_path_buf(), true)
+            .unwrap()
+            .data()
+            .unwrap();
+        let work = data[&Profile::Default]["work"].as_dict().unwrap();
+        assert!(removed);
+        assert_eq!(
+            serde_json::json!(30),
+            serde_json::to_value(&work["vacation_days"]).unwrap()
+        );
+        assert!(!work.contains_key("weekly_hours"));
+    }
+
+    #[rstest]
+    #[case(
+        "config.yaml",
+        "# mine\ndefault:\n  work:\n    weekly_hours: 40\n  data_dir: /tmp\n"
+    )]
+    #[case(
+        "config.json",
+        "{\"default\": {\"work\": {\"weekly_hours\": 40}, \"data_dir\": \"/tmp\"}}"
+    )]
+    #[tokio::test]
+    async fn yaml_and_json_are_rewritten(#[case] name: &str, #[case] content: &str) {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.child(name);
+        file.write_str(content).unwrap();
+
+        set_str(&file, "work.weekly_hours", "32").await.unwrap();
+
+        let text = fs::read_to_string(&file).unwrap();
+        assert!(!text.contains("# mine"));
+        assert!(text.find("data_dir") < text.find("work"));
+        assert!(text.contains("32"));
+    }
+
+    #[test]
+    fn only_files_in_the_user_dir_are_edited() {
+        let user = TempDir::new().unwrap();
+
+        assert_eq!(
+            user.child("work.toml").to_path_buf(),
+            user_file(user.path(), Path::new("work.toml")).unwrap()
+        );
+        assert!(matches!(
+            user_file(user.path(), Path::new("/etc/timers/config.toml")),
+            Err(Error::NotUserConfig(..))
+        ));
+        assert!(matches!(
+            user_file(user.path(), Path::new("../config.toml")),
+            Err(Error::NotUserConfig(..))
+        ));
+    }
+
+    #[tokio::test]
+    async fn unset_missing_keys_changes_nothing() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.child("config.toml");
+        file.write_str(TOML).unwrap();
+
+        let removed = unset(
+            file.to_path_buf(),
+            Profile::Default,
+            "remotes.origin.url".to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert!(!removed);
+        assert_eq!(TOML, fs::read_to_string(&file).unwrap());
+    }
+
+    #[tokio::test]
+    async fn unset_removes_emptied_tables() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.child("config.toml");
+        file.write_str(TOML).unwrap();
+
+        set_str(&file, "remotes.origin.url", "https://example.com/time.git")
+            .await
+            .unwrap();
+        unset(
+            file.to_path_buf(),
+            Profile::Default,
+            "remotes.origin.url".to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(TOML, fs::read_to_string(&file).unwrap());
+    }
+
+    #[tokio::test]
+    async fn values_are_no_tables() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.child("config.toml");
+        file.write_str(TOML).unwrap();
+
+        let result = set_str(&file, "data_dir.nested", "1").await;
+
+        assert!(matches!(result, Err(Error::NotATable(key)) if key == "default.data_dir"));
+    }
+
+    #[tokio::test]
+    async fn defining_files_are_found_per_profile() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("a.toml").write_str(TOML).unwrap();
+        tmp.child("b.yaml")
+            .write_str("work:\n  work:\n    weekly_hours: 20\n")
+            .unwrap();
+        tmp.child("c.json").write_str("{}").unwrap();
+
+        let default = defining(
+            tmp.to_path_buf(),
+            Profile::Default,
+            "work.weekly_hours".to_string(),
+        )
+        .await
+        .unwrap();
+        let work = defining(
+            tmp.to_path_buf(),
+            Profile::new("work"),
+            "work.weekly_hours".to_string(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(vec![tmp.child("a.toml").to_path_buf()], default);
+        assert_eq!(vec![tmp.child("b.yaml").to_path_buf()], work);
+    }
+
+    #[test]
+    fn default_file_is_in_the_user_dir() {
+        let system = TempDir::new().unwrap();
+        let user = TempDir::new().unwrap();
+        let local = TempDir::new().unwrap();
+        system.child("config.toml").touch().unwrap();
+        local.child(LOCAL_DIR).create_dir_all().unwrap();
+        let dirs = [
+            system.to_path_buf(),
+            user.to_path_buf(),
+            local.child(LOCAL_DIR).to_path_buf(),
+        ];
+
+        assert_eq!(
+            Some(user.child(DEFAULT_FILE).to_path_buf()),
+            default_file(&dirs)
+        );
+
+        user.child("10-work.yaml").touch().unwrap();
+        user.child("9-base.toml").touch().unwrap();
+        user.child("README.md").touch().unwrap();
+
+        assert_eq!(
+            Some(user.child("10-work.yaml").to_path_buf()),
+            default_file(&dirs)
+        );
+    }
+}
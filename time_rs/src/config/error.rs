@@ -2,7 +2,7 @@
 //
 // SPDX-License-Identifier: MIT
 
-use std::path::PathBuf;
+use std::{io, path::PathBuf, process::ExitStatus};
 
 use figment::Error as FigmentError;
 use glob::{GlobError, PatternError};
@@ -13,6 +13,10 @@ use tokio::task::JoinError;
 pub enum Error {
     #[error("config can not get extracted")]
     ExtractionError(#[from] Box<FigmentError>),
+    #[error("environment variable `{}` has an invalid value", .0)]
+    Environment(String, #[source] Box<FigmentError>),
+    #[error("unknown profile `{}`, configured are: {}", .0, .1.join(", "))]
+    UnknownProfile(String, Vec<String>),
     #[error("glob pattern matching failed")]
     GlobError(#[from] GlobError),
     #[error("invalid glob pattern: {}", .0)]
@@ -25,6 +29,48 @@ pub enum Error {
     NoExtension(PathBuf),
     #[error("Unable to load the configuration")]
     LoadingConfig(#[source] Box<Error>),
+    #[error("no configuration value at '{}'", .0)]
+    UnknownKey(String),
+    #[error(
+        "'{}' is not a known setting{}",
+        .0,
+        .1.as_ref().map(|s| format!(", did you mean '{s}'?")).unwrap_or_default()
+    )]
+    UnknownSetting(String, Option<String>),
+    #[error("configuration can not be serialized")]
+    Serialize(#[source] serde_json::Error),
+    #[error("{:?} can not be read", .0)]
+    ReadFile(PathBuf, #[source] io::Error),
+    #[error("{:?} can not be written", .0)]
+    WriteFile(PathBuf, #[source] io::Error),
+    #[error("{:?} is not valid TOML", .0)]
+    Toml(PathBuf, #[source] toml_edit::TomlError),
+    #[error("{:?} is not valid YAML", .0)]
+    Yaml(PathBuf, #[source] serde_yaml::Error),
+    #[error("{:?} is not valid JSON", .0)]
+    Json(PathBuf, #[source] serde_json::Error),
+    #[error("'{}' is not a table", .0)]
+    NotATable(String),
+    #[error("the value of '{}' can not be written to the file", .0)]
+    Unrepresentable(String),
+    #[error("{:?} is not part of the user configuration in {:?}, which is the only one that gets changed", .0, .1)]
+    NotUserConfig(PathBuf, PathBuf),
+    #[error("secret file {:?} can not be read", .0)]
+    SecretFile(PathBuf, #[source] io::Error),
+    #[error("secret environment variable `{}` is not set", .0)]
+    SecretEnv(String),
+    #[error("secret command `{}` can not be run", .0)]
+    SecretCommandSpawn(String, #[source] io::Error),
+    #[error("secret command `{}` failed with {}", .0, .1)]
+    SecretCommand(String, ExitStatus),
+    #[error("secret command `{}` printed invalid UTF-8", .0)]
+    SecretCommandOutput(String),
+    #[error("secret commands and files are only read from the user configuration, not from {}", .0)]
+    UntrustedSecret(String),
+    #[error("{:?} sets '{}', project local configuration may only set 'start'", .0, .1)]
+    LocalKey(PathBuf, String),
+    #[error("configuration files can not be watched")]
+    Watch(#[source] notify::Error),
     #[error("couldn't join work units")]
     JoinError(#[source] JoinError),
 }
@@ -0,0 +1,87 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::path::PathBuf;
+
+use jiff::civil::Date;
+use serde::Deserialize;
+use tokio::task;
+
+use super::{file_figment, Error, Result};
+
+/// A public holiday, loaded from the file configured as `work.holidays`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Holiday {
+    pub date: Date,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Definition {
+    #[serde(default)]
+    holidays: Vec<Holiday>,
+}
+
+/// Loads the holidays defined in `file`, ordered by date.
+pub async fn load(file: PathBuf) -> Result<Vec<Holiday>> {
+    let figment = task::spawn_blocking(move || file_figment(file, false))
+        .await
+        .map_err(Error::JoinError)??;
+
+    let mut holidays = figment.extract::<Definition>().map_err(Box::new)?.holidays;
+    holidays.sort_by_key(|h| h.date);
+
+    Ok(holidays)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+    use jiff::civil::date;
+    use rstest::rstest;
+
+    use super::*;
+
+    const TOML: &str = r#"
+    [[holidays]]
+    date = "2026-12-26"
+    name = "Second Day of Christmas"
+
+    [[holidays]]
+    date = "2026-12-25"
+    name = "Christmas Day"
+    "#;
+
+    const YAML: &str = r#"
+    holidays:
+      - date: 2026-12-26
+        name: Second Day of Christmas
+      - date: 2026-12-25
+        name: Christmas Day
+    "#;
+
+    #[rstest]
+    #[case("holidays.toml", TOML)]
+    #[case("holidays.yaml", YAML)]
+    #[tokio::test]
+    async fn loads_sorted_holidays(#[case] name: &str, #[case] content: &str) {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(name).write_str(content).unwrap();
+
+        let holidays = load(tmp.child(name).to_path_buf()).await.unwrap();
+
+        assert_eq!(
+            vec![date(2026, 12, 25), date(2026, 12, 26)],
+            holidays.iter().map(|h| h.date).collect::<Vec<_>>()
+        );
+        assert_eq!("Christmas Day", holidays[0].name);
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_formats() {
+        let result = load(PathBuf::from("holidays.txt")).await;
+
+        assert!(matches!(result, Err(Error::UnknownExtension(_, _))));
+    }
+}
@@ -0,0 +1,321 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::{
+    sync::{mpsc, watch},
+    time,
+};
+
+use super::{is_supported, Config, Error, Result};
+
+/// Time to wait for further events before reloading, editors tend to write a
+/// file in several steps.
+const SETTLE: Duration = Duration::from_millis(100);
+
+/// A [`Config`] that is reloaded whenever one of its files changes, for modes
+/// that run longer than a single command.
+///
+/// Reloaded configurations replace the current one as a whole. If a changed
+/// file does not load, the error is reported by [`Live::error`] and the last
+/// good configuration stays active. Dirs and the project local configuration
+/// that do not exist when watching starts are not picked up later.
+pub struct Live {
+    config: watch::Receiver<Arc<Config>>,
+    errors: mpsc::UnboundedReceiver<Error>,
+    _watcher: RecommendedWatcher,
+}
+
+/// Something that is watched for changes of the configuration.
+enum Target {
+    /// A folder, every supported file below it counts.
+    Dir(PathBuf),
+    /// A single file, its folder is watched to survive editors replacing it.
+    File(PathBuf),
+}
+
+impl Target {
+    fn concerns(&self, path: &Path) -> bool {
+        match self {
+            Target::Dir(dir) => path.starts_with(dir) && is_supported(path),
+            Target::File(file) => path == file,
+        }
+    }
+}
+
+impl Config {
+    /// Watches the dirs and the project local configuration the
+    /// configuration was loaded from. Reloading runs as a task of the tokio
+    /// runtime that awaits this.
+    pub async fn watch(self) -> Result<Live> {
+        let targets = self
+            .dirs
+            .iter()
+            .chain(&self.local)
+            .filter_map(|path| match path {
+                p if p.is_file() => Some(Target::File(p.clone())),
+                p if p.is_dir() => Some(Target::Dir(p.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let (events_tx, mut events) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            // Fails only once the reloading task is gone.
+            let _ = events_tx.send(event);
+        })
+        .map_err(Error::Watch)?;
+
+        for target in &targets {
+            let (path, mode) = match target {
+                Target::Dir(dir) => (dir.as_path(), RecursiveMode::Recursive),
+                Target::File(file) => (
+                    file.parent().unwrap_or(Path::new(".")),
+                    RecursiveMode::NonRecursive,
+                ),
+            };
+            watcher.watch(path, mode).map_err(Error::Watch)?;
+        }
+
+        let (config_tx, config) = watch::channel(Arc::new(self));
+        let (errors_tx, errors) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                match event {
+                    Ok(event) if relevant(&targets, &event) => (),
+                    Ok(_) => continue,
+                    Err(e) => {
+                        let _ = errors_tx.send(Error::Watch(e));
+                        continue;
+                    }
+                }
+
+                while let Ok(Some(_)) = time::timeout(SETTLE, events.recv()).await {}
+
+                let current = config_tx.borrow().clone();
+                match current.reload().await {
+                    Ok(reloaded) => {
+                        config_tx.send_replace(Arc::new(reloaded));
+                    }
+                    Err(e) => {
+                        let _ = errors_tx.send(e);
+                    }
+                }
+            }
+        });
+
+        Ok(Live {
+            config,
+            errors,
+            _watcher: watcher,
+        })
+    }
+}
+
+fn relevant(targets: &[Target], event: &Event) -> bool {
+    let changes = matches!(
+        event.kind,
+        EventKind::Any | EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    );
+
+    changes
+        && event
+            .paths
+            .iter()
+            .any(|path| targets.iter().any(|t| t.concerns(path)))
+}
+
+impl Live {
+    /// The active configuration.
+    pub fn config(&self) -> Arc<Config> {
+        self.config.borrow().clone()
+    }
+
+    /// Waits for the next reloaded configuration, `None` once watching
+    /// stopped.
+    pub async fn changed(&mut self) -> Option<Arc<Config>> {
+        self.config.changed().await.ok()?;
+
+        Some(self.config.borrow_and_update().clone())
+    }
+
+    /// Waits for the next change that could not be loaded.
+    pub async fn error(&mut self) -> Option<Error> {
+        self.errors.recv().await
+    }
+
+    /// A receiver of every reloaded configuration, for other tasks.
+    pub fn subscribe(&self) -> watch::Receiver<Arc<Config>> {
+        self.config.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+    use crate::config::local::LOCAL_FILE;
+
+    const WAIT: Duration = Duration::from_secs(10);
+
+    fn data_dir(value: &str) -> String {
+        format!("[default]\ndata_dir = \"{value}\"\n")
+    }
+
+    async fn live(tmp: &TempDir) -> Live {
+        Config::load_with_env(vec![tmp.to_path_buf()], None, "TIMERS_TEST_LIVE_")
+            .await
+            .unwrap()
+            .watch()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn changes_are_picked_up() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("config.toml")
+            .write_str(&data_dir("/old"))
+            .unwrap();
+        let mut live = live(&tmp).await;
+
+        tmp.child("config.toml")
+            .write_str(&data_dir("/new"))
+            .unwrap();
+
+        let config = time::timeout(WAIT, live.changed()).await.unwrap().unwrap();
+        assert_eq!(Some(PathBuf::from("/new")), config.data_dir);
+        assert_eq!(config.data_dir, live.config().data_dir);
+    }
+
+    #[tokio::test]
+    async fn new_files_are_picked_up() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("config.toml")
+            .write_str(&data_dir("/old"))
+            .unwrap();
+        let mut live = live(&tmp).await;
+
+        tmp.child("work.toml").write_str(&data_dir("/new")).unwrap();
+
+        let config = time::timeout(WAIT, live.changed()).await.unwrap().unwrap();
+        assert_eq!(Some(PathBuf::from("/new")), config.data_dir);
+    }
+
+    #[tokio::test]
+    async fn invalid_changes_keep_the_last_good_config() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("config.toml")
+            .write_str(&data_dir("/old"))
+            .unwrap();
+        let mut live = live(&tmp).await;
+        let before = live.config();
+
+        tmp.child("config.toml")
+            .write_str("[default]\ndata_dir = \"/broken\"\nwork = 42\n")
+            .unwrap();
+
+        let error = time::timeout(WAIT, live.error()).await.unwrap().unwrap();
+        assert!(matches!(
+            error,
+            Error::LoadingConfig(_) | Error::ExtractionError(_)
+        ));
+        assert!(Arc::ptr_eq(&before, &live.config()));
+        assert_eq!(Some(PathBuf::from("/old")), live.config().data_dir);
+
+        tmp.child("config.toml")
+            .write_str(&data_dir("/fixed"))
+            .unwrap();
+
+        let config = time::timeout(WAIT, live.changed()).await.unwrap().unwrap();
+        assert_eq!(Some(PathBuf::from("/fixed")), config.data_dir);
+    }
+
+    #[tokio::test]
+    async fn local_changes_are_picked_up() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("config.toml")
+            .write_str(&data_dir("/old"))
+            .unwrap();
+        let project = TempDir::new().unwrap();
+        let local = project.child(LOCAL_FILE);
+        local
+            .write_str("[default.start]\nproject = \"old\"\n")
+            .unwrap();
+        let mut live = Config::load_with_env(
+            vec![tmp.to_path_buf()],
+            Some(local.to_path_buf()),
+            "TIMERS_TEST_LIVE_",
+        )
+        .await
+        .unwrap()
+        .watch()
+        .await
+        .unwrap();
+
+        local
+            .write_str("[default.start]\nproject = \"new\"\n")
+            .unwrap();
+
+        let config = time::timeout(WAIT, live.changed()).await.unwrap().unwrap();
+        assert_eq!(Some("new".to_string()), config.start.project);
+        assert_eq!(Some(PathBuf::from("/old")), config.data_dir);
+    }
+
+    #[tokio::test]
+    async fn local_changes_setting_more_than_start_are_refused() {
+        let tmp = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        let local = project.child(LOCAL_FILE);
+        local
+            .write_str("[default.start]\nproject = \"old\"\n")
+            .unwrap();
+        let mut live = Config::load_with_env(
+            vec![tmp.to_path_buf()],
+            Some(local.to_path_buf()),
+            "TIMERS_TEST_LIVE_",
+        )
+        .await
+        .unwrap()
+        .watch()
+        .await
+        .unwrap();
+
+        local.write_str("[default]\ndata_dir = \"/x\"\n").unwrap();
+
+        let error = time::timeout(WAIT, live.error()).await.unwrap().unwrap();
+        assert!(matches!(error, Error::LocalKey(..)));
+        assert_eq!(Some("old".to_string()), live.config().start.project);
+    }
+
+    #[tokio::test]
+    async fn changes_made_after_loading_are_kept() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("config.toml")
+            .write_str("[default]\ndata_dir = \"/old\"\n[work]\ndata_dir = \"/work\"\n")
+            .unwrap();
+        let mut config = Config::load_with_env(vec![tmp.to_path_buf()], None, "TIMERS_TEST_LIVE_")
+            .await
+            .unwrap();
+        config.select_profile("work").unwrap();
+        config.add_default_data_dir("/default").unwrap();
+        let mut live = config.watch().await.unwrap();
+
+        tmp.child("config.toml")
+            .write_str("[default]\ndata_dir = \"/old\"\n[work]\ndata_dir = \"/new\"\n")
+            .unwrap();
+
+        let config = time::timeout(WAIT, live.changed()).await.unwrap().unwrap();
+        assert_eq!("work", config.profile().as_str());
+        assert_eq!(Some(PathBuf::from("/new")), config.data_dir);
+    }
+}
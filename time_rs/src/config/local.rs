@@ -0,0 +1,160 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Configuration of a single checkout, e.g. the project to track time for.
+//!
+//! Any checkout can ship such a file, including the data repository itself,
+//! so it may only set harmless defaults. Everything that could redirect the
+//! data or make `timers` run commands is only read from the user's own
+//! configuration.
+
+use std::path::{Path, PathBuf};
+
+use figment::{
+    value::{Dict, Map},
+    Figment, Profile, Provider,
+};
+
+use super::{discover, file_figment, is_supported, Error, Result};
+
+/// Top level keys a project local configuration may set.
+pub const ALLOWED_KEYS: [&str; 1] = ["start"];
+
+/// Configuration file of a single checkout.
+pub const LOCAL_FILE: &str = ".timers.toml";
+
+/// Configuration folder of a single checkout, for more than a single file.
+pub const LOCAL_DIR: &str = ".timers";
+
+/// The closest [`LOCAL_FILE`] or [`LOCAL_DIR`] in `dir` or one of its
+/// ancestors, the file wins if a folder has both.
+pub fn find(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors().find_map(|dir| {
+        [dir.join(LOCAL_FILE), dir.join(LOCAL_DIR)]
+            .into_iter()
+            .find(|candidate| match candidate.file_name() {
+                Some(name) if name == LOCAL_FILE => candidate.is_file(),
+                _ => candidate.is_dir(),
+            })
+    })
+}
+
+/// Loads the project local configuration at `path`, a file or folder, and
+/// refuses it if it sets anything but [`ALLOWED_KEYS`].
+pub(crate) fn figment(path: &Path) -> Result<Figment> {
+    let mut figment = Figment::new();
+
+    for file in discover(&[path.to_owned()])?
+        .into_iter()
+        .filter(|f| is_supported(f))
+    {
+        let additional = file_figment(file.clone(), true)?;
+        let data = additional.data().map_err(Box::new)?;
+        if let Some(key) = restricted(&data).into_iter().next() {
+            return Err(Error::LocalKey(file, key.join(".")));
+        }
+        figment = figment.merge(additional);
+    }
+
+    Ok(figment)
+}
+
+/// Keys of `data` outside of [`ALLOWED_KEYS`], starting with the profile.
+pub(crate) fn restricted(data: &Map<Profile, Dict>) -> Vec<Vec<String>> {
+    data.iter()
+        .flat_map(|(profile, dict)| {
+            dict.keys()
+                .filter(|key| !ALLOWED_KEYS.contains(&key.as_str()))
+                .map(|key| vec![profile.to_string(), key.clone()])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+
+    #[test]
+    fn file_in_an_ancestor_is_found() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(LOCAL_FILE).touch().unwrap();
+        let nested = tmp.child("src/cli");
+        nested.create_dir_all().unwrap();
+
+        assert_eq!(Some(tmp.child(LOCAL_FILE).to_path_buf()), find(&nested));
+    }
+
+    #[test]
+    fn closest_folder_wins() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(LOCAL_FILE).touch().unwrap();
+        tmp.child("checkout")
+            .child(LOCAL_DIR)
+            .create_dir_all()
+            .unwrap();
+
+        assert_eq!(
+            Some(tmp.child("checkout").child(LOCAL_DIR).to_path_buf()),
+            find(&tmp.child("checkout"))
+        );
+    }
+
+    #[test]
+    fn file_wins_over_folder() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(LOCAL_FILE).touch().unwrap();
+        tmp.child(LOCAL_DIR).create_dir_all().unwrap();
+
+        assert_eq!(Some(tmp.child(LOCAL_FILE).to_path_buf()), find(&tmp));
+    }
+
+    #[test]
+    fn folders_named_like_the_file_are_skipped() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(LOCAL_FILE).create_dir_all().unwrap();
+
+        assert_eq!(None, find(&tmp));
+    }
+
+    #[test]
+    fn start_defaults_are_loaded() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(LOCAL_FILE)
+            .write_str("[default.start]\nproject = \"time_rs\"\n")
+            .unwrap();
+
+        let figment = figment(tmp.child(LOCAL_FILE).path()).unwrap();
+
+        assert_eq!(
+            "time_rs",
+            figment.extract_inner::<String>("start.project").unwrap()
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::data_dir("[default]\ndata_dir = \"/tmp/elsewhere\"\n", "default.data_dir")]
+    #[case::remotes(
+        "[default.remotes.x]\nurl = \"https://example.com\"\n",
+        "default.remotes"
+    )]
+    #[case::secrets(
+        "[work.remotes.x]\nurl = \"u\"\ntoken = { command = \"rm -rf ~\" }\n",
+        "work.remotes"
+    )]
+    #[case::sync("[default.sync]\nauto = \"after-write\"\n", "default.sync")]
+    #[case::lock("[default.lock]\ntimeout = 0\n", "default.lock")]
+    fn everything_else_is_refused(#[case] content: &str, #[case] key: &str) {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(LOCAL_DIR)
+            .child("a.toml")
+            .write_str(content)
+            .unwrap();
+
+        let result = figment(tmp.child(LOCAL_DIR).path());
+
+        assert!(matches!(result, Err(Error::LocalKey(_, k)) if k == key));
+    }
+}
@@ -0,0 +1,34 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// How long commands that change the data wait for other `timers` processes.
+#[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
+pub struct LockSettings {
+    /// Seconds to wait for the lock of the data dir, before giving up.
+    #[serde(default = "default_timeout")]
+    pub timeout: u64,
+}
+
+impl Default for LockSettings {
+    fn default() -> Self {
+        Self {
+            timeout: default_timeout(),
+        }
+    }
+}
+
+impl LockSettings {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout)
+    }
+}
+
+fn default_timeout() -> u64 {
+    10
+}
@@ -3,29 +3,117 @@
 // SPDX-License-Identifier: MIT
 
 use std::{
+    collections::BTreeMap,
     fmt::Debug,
+    mem,
     path::{Path, PathBuf},
 };
 
 use figment::{
-    providers::{Format, Json, Toml, Yaml},
-    Figment,
+    providers::{Data, Env, Format, Json, Serialized, Toml, Yaml},
+    value::{Tag, Value},
+    Figment, Profile, Provider, Source,
 };
+use schemars::JsonSchema;
 use serde::Deserialize;
 use tokio::task;
 
+pub use crate::config::compliance::Compliance;
 pub use crate::config::error::Error;
-
+pub use crate::config::holidays::Holiday;
+pub use crate::config::live::Live;
+pub use crate::config::lock::LockSettings;
+pub use crate::config::projects::{Budget, Project};
+use crate::config::provenance::Named;
+pub use crate::config::provenance::Provenance;
+pub use crate::config::remotes::Remote;
+pub use crate::config::secret::{Origin, Secret};
+pub use crate::config::start::StartDefaults;
+pub use crate::config::sync::{AutoSync, SyncSettings};
+pub use crate::config::validate::Finding;
+pub use crate::config::work::Work;
+
+pub mod compliance;
+pub mod edit;
 pub mod error;
+pub mod holidays;
+pub mod live;
+pub mod local;
+pub mod lock;
+pub mod projects;
+pub mod provenance;
+pub mod remotes;
+pub mod schema;
+pub mod secret;
+pub mod start;
+pub mod sync;
+pub mod validate;
+pub mod work;
 
 type Result<T> = std::result::Result<T, Error>;
 
-#[derive(Debug, Deserialize)]
+/// Prefix of environment variables that override the configuration files,
+/// nested keys are separated by [`ENV_SEPARATOR`], e.g. `TIMERS_WORK__WEEKLY_HOURS`.
+pub const ENV_PREFIX: &str = "TIMERS_";
+
+pub const ENV_SEPARATOR: &str = "__";
+
+/// Settings of a single profile.
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct Config {
+    /// Folder that holds the time data repository.
     pub data_dir: Option<PathBuf>,
 
+    /// Working hour targets and the flex time account.
+    #[serde(default)]
+    pub work: Work,
+
+    /// Labor-law rules that recorded time is checked against.
+    #[serde(default)]
+    pub compliance: Compliance,
+
+    /// Per project settings, keyed by the project name.
+    #[serde(default)]
+    pub projects: BTreeMap<String, Project>,
+
+    /// Defaults for `timers start`.
+    #[serde(default)]
+    pub start: StartDefaults,
+
+    /// Remotes the data repository is synced with, keyed by their name.
+    #[serde(default)]
+    pub remotes: BTreeMap<String, Remote>,
+
+    /// Waiting for other `timers` processes that change the data.
+    #[serde(default)]
+    pub lock: LockSettings,
+
+    /// Syncing the data repository with its remotes.
+    #[serde(default)]
+    pub sync: SyncSettings,
+
     #[serde(skip)]
     figment: Figment,
+
+    #[serde(skip)]
+    dirs: Vec<PathBuf>,
+
+    #[serde(skip)]
+    local: Option<PathBuf>,
+
+    #[serde(skip)]
+    prefix: String,
+
+    #[serde(skip)]
+    layers: Vec<Layer>,
+}
+
+/// Changes made after loading, which are applied again on [`Config::reload`].
+#[derive(Debug, Clone)]
+enum Layer {
+    DataDir(PathBuf),
+    DefaultDataDir(PathBuf),
+    Profile(String),
 }
 
 impl Config {
@@ -33,60 +121,342 @@ impl Config {
         let figment = Figment::new();
 
         task::spawn_blocking(move || {
-            paths
-                .iter()
-                .map(|p| p.join("**").join("*.*"))
-                .map(|p| {
-                    p.to_str()
-                        .map(|s| s.to_string())
-                        .ok_or_else(|| Error::PathStringConversion(p.clone()))
-                })
-                .flat_map(|s| {
-                    let s = s?;
-                    glob::glob(&s).map_err(|e| Error::PatternError(s.clone(), e))
-                })
-                .flatten()
-                .map(|f| {
-                    let file = f?;
-                    let ext = file.extension().and_then(|s| s.to_str());
-
-                    match ext {
-                        Some("yaml" | "yml") => Ok(Figment::from(Yaml::file(file).nested())),
-                        Some("toml") => Ok(Figment::from(Toml::file(file).nested())),
-                        Some("json") => Ok(Figment::from(Json::file(file).nested())),
-                        Some(ext) => Err(Error::UnknownExtension(file.clone(), ext.to_owned())),
-                        None => Err(Error::NoExtension(file.clone())),
-                    }
-                })
+            discover(&paths)?
+                .into_iter()
+                .filter(|f| is_supported(f))
+                .map(|f| file_figment(f, true))
                 .try_fold(figment, |acc, additional| Ok(acc.merge(additional?)))
         })
         .await
         .map_err(Error::JoinError)?
     }
 
+    /// Loads all files below `paths`, later paths win. The binary passes the
+    /// system dirs, then the user dir. Within a folder, files are merged in
+    /// [`merge_order`]. All files are overridden by `TIMERS_*` environment
+    /// variables, which are overridden by command line flags.
     pub async fn load(paths: Vec<PathBuf>) -> Result<Self> {
-        let figment = Self::load_figment(paths)
+        Self::load_with_local(paths, None).await
+    }
+
+    /// Like [`Config::load`], with the project `local` dir or
+    /// [`local::LOCAL_FILE`] on top of the files, which may only set
+    /// [`local::ALLOWED_KEYS`].
+    pub async fn load_with_local(paths: Vec<PathBuf>, local: Option<PathBuf>) -> Result<Self> {
+        Self::load_with_env(paths, local, ENV_PREFIX).await
+    }
+
+    async fn load_with_env(
+        paths: Vec<PathBuf>,
+        local: Option<PathBuf>,
+        prefix: &str,
+    ) -> Result<Self> {
+        let env_name = environment(prefix).metadata().name;
+
+        let mut figment = Self::load_figment(paths.clone())
             .await
             .map_err(|e| Error::LoadingConfig(Box::new(e)))?;
+        if let Some(ref local) = local {
+            let path = local.clone();
+            let local = task::spawn_blocking(move || local::figment(&path))
+                .await
+                .map_err(Error::JoinError)??;
+            figment = figment.merge(local);
+        }
+        let figment = figment.merge(environment(prefix));
+
+        let config = Config::try_from(figment).map_err(|e| match e {
+            Error::ExtractionError(e)
+                if e.metadata.as_ref().is_some_and(|md| md.name == env_name) =>
+            {
+                let var = format!("{prefix}{}", e.path.join(ENV_SEPARATOR)).to_uppercase();
+                Error::Environment(var, e)
+            }
+            e => e,
+        })?;
 
-        figment.try_into()
+        Ok(Config {
+            dirs: paths,
+            local,
+            prefix: prefix.to_owned(),
+            ..config
+        })
     }
 
+    /// Loads the files again, along with the environment and every change
+    /// made since the last load.
+    pub async fn reload(&self) -> Result<Self> {
+        let mut config =
+            Self::load_with_env(self.dirs.clone(), self.local.clone(), &self.prefix).await?;
+
+        for layer in &self.layers {
+            match layer {
+                Layer::DataDir(path) => config.add_data_dir(path)?,
+                Layer::DefaultDataDir(path) => config.add_default_data_dir(path)?,
+                Layer::Profile(profile) => config.select_profile(profile)?,
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// A configuration without any values that still knows the `dirs` and
+    /// `local` config it should have been loaded from, to validate files that
+    /// fail to load.
+    pub fn unloaded(dirs: Vec<PathBuf>, local: Option<PathBuf>) -> Self {
+        Config {
+            data_dir: None,
+            work: Work::default(),
+            compliance: Compliance::default(),
+            projects: BTreeMap::new(),
+            start: StartDefaults::default(),
+            remotes: BTreeMap::new(),
+            lock: LockSettings::default(),
+            sync: SyncSettings::default(),
+            figment: Figment::new(),
+            dirs,
+            local,
+            prefix: ENV_PREFIX.to_owned(),
+            layers: vec![],
+        }
+    }
+
+    /// Re-extracts the configuration from a changed `figment`.
+    fn replace(&mut self, figment: Figment) -> Result<()> {
+        let config = Config::try_from(figment)?;
+
+        *self = Config {
+            dirs: mem::take(&mut self.dirs),
+            local: self.local.take(),
+            prefix: mem::take(&mut self.prefix),
+            layers: mem::take(&mut self.layers),
+            ..config
+        };
+
+        Ok(())
+    }
+
+    /// Overrides the data dir with the one given on the command line.
     pub fn add_data_dir<P>(&mut self, path: P) -> Result<()>
     where
         P: AsRef<Path> + Debug,
     {
-        let figment = self.figment.clone().merge((
-            "data_dir",
-            path.as_ref()
-                .to_str()
-                .ok_or_else(|| Error::PathStringConversion(path.as_ref().to_owned()))?,
-        ));
+        let provider = Named::new(
+            "command line flag `--data-dir`",
+            Serialized::global("data_dir", path_str(path.as_ref())?),
+        );
+
+        self.replace(self.figment.clone().merge(provider))?;
+        self.layers.push(Layer::DataDir(path.as_ref().to_owned()));
+
+        Ok(())
+    }
+
+    /// Uses `path` as data dir, unless one has been configured already.
+    pub fn add_default_data_dir<P>(&mut self, path: P) -> Result<()>
+    where
+        P: AsRef<Path> + Debug,
+    {
+        let provider = Named::new(
+            "default data dir",
+            Serialized::default("data_dir", path_str(path.as_ref())?),
+        );
+
+        self.replace(self.figment.clone().join(provider))?;
+        self.layers
+            .push(Layer::DefaultDataDir(path.as_ref().to_owned()));
+
+        Ok(())
+    }
+
+    /// Uses the values of `profile` on top of the `default` ones, selecting
+    /// `default` itself uses just those.
+    pub fn select_profile(&mut self, profile: &str) -> Result<()> {
+        let known = self
+            .figment
+            .profiles()
+            .filter(|p| *p != Profile::Default && *p != Profile::Global)
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>();
+
+        if Profile::new(profile) != Profile::Default
+            && !known.iter().any(|p| p.eq_ignore_ascii_case(profile))
+        {
+            return Err(Error::UnknownProfile(profile.to_owned(), known));
+        }
 
-        *self = figment.clone().try_into()?;
+        self.replace(self.figment.clone().select(profile))?;
+        self.layers.push(Layer::Profile(profile.to_owned()));
 
         Ok(())
     }
+
+    /// Selects `profile` without checking that it is configured, for an
+    /// [unloaded](Config::unloaded) configuration that is to be repaired.
+    pub fn assume_profile(&mut self, profile: &str) {
+        self.figment = self.figment.clone().select(profile);
+    }
+
+    /// The selected profile.
+    pub fn profile(&self) -> &Profile {
+        self.figment.profile()
+    }
+
+    /// Whether the configuration still loads with `key` set to `value`.
+    pub fn check_value(&self, key: &str, value: &figment::value::Value) -> Result<()> {
+        let provider = Named::new("new value", Serialized::global(key, value));
+
+        Config::try_from(self.figment.clone().merge(provider)).map(|_| ())
+    }
+
+    /// The directories the configuration files were loaded from, without the
+    /// project local one.
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// The project local configuration, if there is one.
+    pub fn local(&self) -> Option<&Path> {
+        self.local.as_deref()
+    }
+
+    /// The merged configuration sources.
+    pub fn figment(&self) -> &Figment {
+        &self.figment
+    }
+
+    /// Where the value at `key` comes from. It is the user's if every part of
+    /// it is set by a file in the user dir, the last of [`Config::dirs`], or
+    /// by the environment.
+    pub fn origin(&self, key: &str) -> Origin {
+        let env = environment(&self.prefix).metadata().name;
+        let user = self.dirs.last();
+        let Ok(value) = self.figment.find_value(key) else {
+            return Origin::Other(format!("an unknown source of '{key}'"));
+        };
+
+        let mut tags = vec![];
+        leaf_tags(&value, &mut tags);
+        for tag in tags {
+            let Some(metadata) = self.figment.get_metadata(tag) else {
+                return Origin::Other(format!("an unknown source of '{key}'"));
+            };
+            let by_user = match metadata.source {
+                Some(Source::File(ref file)) => user.is_some_and(|dir| file.starts_with(dir)),
+                _ => metadata.name == env,
+            };
+            if !by_user {
+                let source = match metadata.source {
+                    Some(Source::File(ref file)) => file.display().to_string(),
+                    _ => metadata.name.to_string(),
+                };
+                return Origin::Other(source);
+            }
+        }
+
+        Origin::User
+    }
+
+    /// Every effective value below `key`, along with the source that set it.
+    pub fn provenance(&self, key: Option<&str>) -> Result<Vec<Provenance>> {
+        provenance::collect(&self.figment, key)
+    }
+}
+
+/// Environment variables apply to every profile, `TIMERS_PROFILE` selects the
+/// profile on the command line and is not part of the configuration.
+fn environment(prefix: &str) -> Env {
+    Env::prefixed(prefix)
+        .ignore(&["profile"])
+        .split(ENV_SEPARATOR)
+        .global()
+}
+
+fn leaf_tags(value: &Value, out: &mut Vec<Tag>) {
+    match value {
+        Value::Dict(_, dict) => dict.values().for_each(|v| leaf_tags(v, out)),
+        Value::Array(_, items) => items.iter().for_each(|v| leaf_tags(v, out)),
+        leaf => out.push(leaf.tag()),
+    }
+}
+
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| Error::PathStringConversion(path.to_owned()))
+}
+
+/// Every file below `paths`, whether it is supported or not. The paths keep
+/// their order, the files within a folder are sorted by [`merge_order`].
+/// Paths that are files are taken as they are.
+pub(crate) fn discover(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+
+    for path in paths {
+        if path.is_file() {
+            files.push(path.clone());
+            continue;
+        }
+
+        let pattern = path.join("**").join("*.*");
+        let pattern = pattern
+            .to_str()
+            .ok_or_else(|| Error::PathStringConversion(pattern.clone()))?;
+        let mut found = glob::glob(pattern)
+            .map_err(|e| Error::PatternError(pattern.to_owned(), e))?
+            .map(|f| Ok(f?))
+            .filter(|f| !matches!(f, Ok(f) if f.is_dir()))
+            .collect::<Result<Vec<_>>>()?;
+
+        found.sort_by_cached_key(|f| merge_order(f.strip_prefix(path).unwrap_or(f)));
+        files.extend(found);
+    }
+
+    Ok(files)
+}
+
+/// Sort key of a file within a config folder, later files win. Per path
+/// component, names with a numeric `NN-` prefix come first in numeric order,
+/// e.g. `9-base.toml` before `10-work.toml`, all others follow in lexical
+/// order.
+fn merge_order(path: &Path) -> Vec<(bool, u64, String)> {
+    path.components()
+        .map(|c| {
+            let name = c.as_os_str().to_string_lossy().into_owned();
+            let number = name
+                .split_once('-')
+                .and_then(|(prefix, _)| prefix.parse::<u64>().ok());
+
+            (number.is_none(), number.unwrap_or_default(), name)
+        })
+        .collect()
+}
+
+/// Whether `file` has the extension of a supported format, others are
+/// ignored, e.g. a README next to the configuration.
+pub(crate) fn is_supported(file: &Path) -> bool {
+    file.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| EXTENSIONS.contains(&ext))
+}
+
+const EXTENSIONS: [&str; 4] = ["yaml", "yml", "toml", "json"];
+
+/// Loads a single `file`, the format is chosen by its extension. `nested`
+/// files have their top level keys interpreted as profiles.
+pub(crate) fn file_figment(file: PathBuf, nested: bool) -> Result<Figment> {
+    fn provider<F: Format>(data: Data<F>, nested: bool) -> Figment {
+        Figment::from(if nested { data.nested() } else { data })
+    }
+
+    let ext = file.extension().and_then(|s| s.to_str());
+
+    match ext {
+        Some("yaml" | "yml") => Ok(provider(Yaml::file(file), nested)),
+        Some("toml") => Ok(provider(Toml::file(file), nested)),
+        Some("json") => Ok(provider(Json::file(file), nested)),
+        Some(ext) => Err(Error::UnknownExtension(file.clone(), ext.to_owned())),
+        None => Err(Error::NoExtension(file.clone())),
+    }
 }
 
 impl TryFrom<Figment> for Config {
@@ -102,7 +472,7 @@ impl TryFrom<Figment> for Config {
 
 #[cfg(test)]
 mod tests {
-    use std::future::Future;
+    use std::{env, future::Future};
 
     use super::*;
 
@@ -245,21 +615,355 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_unknown_file_extension() {
+    async fn default_data_dir_does_not_override_configuration() {
+        let (tmp, _path, _figment, _name) = figment("config.toml", TOML).await;
+        let mut config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+
+        config.add_default_data_dir("/default").unwrap();
+
+        assert_eq!(Some(PathBuf::from("/tmp")), config.data_dir);
+    }
+
+    #[tokio::test]
+    async fn environment_overrides_files() {
+        let (tmp, _path, _figment, _name) = figment("config.toml", TOML).await;
+        env::set_var("TIMERS_TEST_OVERRIDE_DATA_DIR", "/env");
+        env::set_var("TIMERS_TEST_OVERRIDE_WORK__WEEKLY_HOURS", "32");
+
+        let config = Config::load_with_env(vec![tmp.to_path_buf()], None, "TIMERS_TEST_OVERRIDE_")
+            .await
+            .unwrap();
+
+        assert_eq!(Some(PathBuf::from("/env")), config.data_dir);
+        assert_eq!(Some(32.0), config.work.weekly_hours);
+    }
+
+    #[tokio::test]
+    async fn command_line_overrides_environment() {
+        env::set_var("TIMERS_TEST_CLI_DATA_DIR", "/env");
+
+        let mut config = Config::load_with_env(vec![], None, "TIMERS_TEST_CLI_")
+            .await
+            .unwrap();
+        config.add_data_dir("/cli").unwrap();
+
+        assert_eq!(Some(PathBuf::from("/cli")), config.data_dir);
+    }
+
+    /// The data dir picked the way the binary does, from a config file, an
+    /// environment variable, a `--data-dir` flag and `XDG_DATA_HOME`.
+    #[rstest]
+    #[case(Some("/file"), Some("/env"), Some("/cli"), "/cli")]
+    #[case(Some("/file"), Some("/env"), None, "/env")]
+    #[case(Some("/file"), None, None, "/file")]
+    #[case(None, None, None, "/xdg")]
+    #[tokio::test]
+    async fn data_dirs_follow_the_documented_precedence(
+        #[case] file: Option<&str>,
+        #[case] env: Option<&str>,
+        #[case] cli: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let tmp = TempDir::new().unwrap();
+        if let Some(file) = file {
+            tmp.child("config.toml")
+                .write_str(&format!("[default]\ndata_dir = \"{file}\"\n"))
+                .unwrap();
+        }
+        let prefix = format!(
+            "TIMERS_TEST_PRECEDENCE_{}_",
+            [file, env, cli].iter().filter(|v| v.is_some()).count()
+        );
+        if let Some(env) = env {
+            env::set_var(format!("{prefix}DATA_DIR"), env);
+        }
+
+        let mut config = Config::load_with_env(vec![tmp.to_path_buf()], None, &prefix)
+            .await
+            .unwrap();
+        match cli {
+            Some(cli) => config.add_data_dir(cli).unwrap(),
+            None => config.add_default_data_dir("/xdg").unwrap(),
+        }
+
+        assert_eq!(Some(PathBuf::from(expected)), config.data_dir);
+    }
+
+    #[tokio::test]
+    async fn bad_environment_values_name_the_variable() {
+        env::set_var("TIMERS_TEST_BAD_WORK__WEEKLY_HOURS", "lots");
+
+        let result = Config::load_with_env(vec![], None, "TIMERS_TEST_BAD_").await;
+
+        match result {
+            Err(Error::Environment(var, _)) => {
+                assert_eq!("TIMERS_TEST_BAD_WORK__WEEKLY_HOURS", var)
+            }
+            other => panic!("Error::Environment was expected, got {other:?}"),
+        }
+    }
+
+    const PROFILES: &str = r#"
+    [default]
+    data_dir = "/tmp"
+
+    [default.work]
+    weekly_hours = 40
+
+    [freelance]
+    data_dir = "/freelance"
+    "#;
+
+    #[tokio::test]
+    async fn profiles_override_defaults() {
+        let (tmp, _path, _figment, _name) = figment("config.toml", PROFILES).await;
+        let mut config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+
+        config.select_profile("freelance").unwrap();
+
+        assert_eq!("freelance", config.profile().as_str());
+        assert_eq!(Some(PathBuf::from("/freelance")), config.data_dir);
+        assert_eq!(Some(40.0), config.work.weekly_hours);
+    }
+
+    #[tokio::test]
+    async fn profiles_keep_the_command_line_data_dir() {
+        let (tmp, _path, _figment, _name) = figment("config.toml", PROFILES).await;
+        let mut config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+
+        config.select_profile("freelance").unwrap();
+        config.add_data_dir("/cli").unwrap();
+        config.add_default_data_dir("/default").unwrap();
+
+        assert_eq!(Some(PathBuf::from("/cli")), config.data_dir);
+    }
+
+    #[rstest]
+    #[case("default")]
+    #[case("DEFAULT")]
+    #[tokio::test]
+    async fn the_default_profile_can_be_selected(#[case] profile: &str) {
+        let (tmp, _path, _figment, _name) = figment("config.toml", PROFILES).await;
+        let mut config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+
+        config.select_profile(profile).unwrap();
+
+        assert_eq!(Profile::Default, *config.profile());
+        assert_eq!(Some(PathBuf::from("/tmp")), config.data_dir);
+        assert_eq!(Some(40.0), config.work.weekly_hours);
+    }
+
+    #[tokio::test]
+    async fn unknown_profiles_are_rejected() {
+        let (tmp, _path, _figment, _name) = figment("config.toml", PROFILES).await;
+        let mut config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+
+        let result = config.select_profile("personal");
+
+        match result {
+            Err(Error::UnknownProfile(name, known)) => {
+                assert_eq!("personal", name);
+                assert_eq!(vec!["freelance".to_string()], known);
+            }
+            other => panic!("Error::UnknownProfile was expected, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn unsupported_files_are_ignored() {
         let tmpdir = TempDir::new().unwrap();
         tmpdir.child("foo.txt").touch().unwrap();
+        tmpdir.child("README.md").write_str("# My config").unwrap();
+        tmpdir.child("config.toml").write_str(TOML).unwrap();
+
+        let config = Config::load(vec![tmpdir.to_path_buf()]).await.unwrap();
+
+        assert_eq!(Some(PathBuf::from("/tmp")), config.data_dir);
+        let files = config
+            .figment()
+            .metadata()
+            .filter_map(|md| md.source.as_ref()?.file_path())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![tmpdir.child("config.toml").path()], files);
+    }
 
-        let config = Config::load(vec![tmpdir.to_path_buf()]).await;
+    #[tokio::test]
+    async fn local_files_set_start_defaults_below_the_environment() {
+        let (tmp, _path, _figment, _name) = figment(
+            "config.toml",
+            "[default]\ndata_dir = \"/tmp\"\n[default.start]\nclient = \"user\"\n",
+        )
+        .await;
+        let local = TempDir::new().unwrap();
+        let file = local.child(".timers.toml");
+        file.write_str("[default.start]\nproject = \"time_rs\"\nclient = \"local\"\n")
+            .unwrap();
+        env::set_var("TIMERS_TEST_LOCAL_START__PROJECT", "env");
+
+        let config = Config::load_with_env(
+            vec![tmp.to_path_buf()],
+            Some(file.to_path_buf()),
+            "TIMERS_TEST_LOCAL_",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(Some(PathBuf::from("/tmp")), config.data_dir);
+        assert_eq!(Some("local".to_string()), config.start.client);
+        assert_eq!(Some("env".to_string()), config.start.project);
+        assert_eq!(Some(file.path()), config.local());
+        assert_eq!(&[tmp.to_path_buf()], config.dirs());
+    }
+
+    #[tokio::test]
+    async fn local_files_can_not_redirect_the_data() {
+        let (tmp, _path, _figment, _name) = figment("config.toml", TOML).await;
+        let local = TempDir::new().unwrap();
+        let file = local.child(".timers.toml");
+        file.write_str("[default]\ndata_dir = \"/local\"\n")
+            .unwrap();
+
+        let result =
+            Config::load_with_local(vec![tmp.to_path_buf()], Some(file.to_path_buf())).await;
+
+        assert!(matches!(result, Err(Error::LocalKey(_, key)) if key == "default.data_dir"));
+    }
+
+    fn data_dir(ext: &str, value: &str) -> String {
+        match ext {
+            "toml" => format!("[default]\ndata_dir = \"{value}\"\n"),
+            "json" => format!("{{\"default\": {{\"data_dir\": \"{value}\"}}}}"),
+            _ => format!("default:\n  data_dir: {value}\n"),
+        }
+    }
+
+    #[rstest]
+    #[case("toml")]
+    #[case("yaml")]
+    #[case("yml")]
+    #[case("json")]
+    #[tokio::test]
+    async fn files_are_merged_in_order(#[case] ext: &str) {
+        let tmp = TempDir::new().unwrap();
+        let names = [
+            "2-base",
+            "9-work",
+            "10-late",
+            "b-plain",
+            "c-plain",
+            "d/1-nested",
+            "e",
+        ];
+        // created in reverse to not depend on the order of the file system
+        for name in names.iter().rev() {
+            tmp.child(format!("{name}.{ext}"))
+                .write_str(&data_dir(ext, name))
+                .unwrap();
+        }
+
+        let config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+
+        let files = config
+            .figment()
+            .metadata()
+            .filter_map(|md| md.source.as_ref()?.file_path())
+            .map(|p| p.strip_prefix(tmp.path()).unwrap().to_owned())
+            .collect::<Vec<_>>();
+        let expected = names
+            .iter()
+            .map(|name| PathBuf::from(format!("{name}.{ext}")))
+            .collect::<Vec<_>>();
+        assert_eq!(expected, files);
+        assert_eq!(Some(PathBuf::from("e")), config.data_dir);
+    }
+
+    #[tokio::test]
+    async fn later_dirs_win() {
+        let system = TempDir::new().unwrap();
+        let user = TempDir::new().unwrap();
+        system
+            .child("99-system.toml")
+            .write_str(&data_dir("toml", "/system"))
+            .unwrap();
+        user.child("config.toml")
+            .write_str(&data_dir("toml", "/user"))
+            .unwrap();
+
+        let config = Config::load(vec![system.to_path_buf(), user.to_path_buf()])
+            .await
+            .unwrap();
+
+        assert_eq!(Some(PathBuf::from("/user")), config.data_dir);
+    }
 
-        assert!(config.is_err());
-        let err = config.unwrap_err();
+    #[test]
+    fn unknown_file_extension_in_file_figment() {
+        let err = file_figment(PathBuf::from("foo.txt"), true).unwrap_err();
 
         match err {
-            Error::LoadingConfig(ref inner) => match &**inner {
-                Error::UnknownExtension(_, ref ext) => assert_eq!(ext, "txt"),
-                err => panic!("Error::UnknownExtension was expected, got {err:?}"),
-            },
-            err => panic!("Error::LoadingConfig was expected, got {err:?}"),
+            Error::UnknownExtension(_, ref ext) => assert_eq!(ext, "txt"),
+            err => panic!("Error::UnknownExtension was expected, got {err:?}"),
         }
     }
+
+    const COMMAND_TOKEN: &str =
+        "[default.remotes.origin]\nurl = \"https://example.com\"\ntoken = { command = \"echo hi\" }\n";
+
+    #[tokio::test]
+    async fn secrets_know_whether_the_user_set_them() {
+        let system = TempDir::new().unwrap();
+        let user = TempDir::new().unwrap();
+        system
+            .child("config.toml")
+            .write_str(COMMAND_TOKEN)
+            .unwrap();
+
+        let config = Config::load(vec![system.to_path_buf(), user.to_path_buf()])
+            .await
+            .unwrap();
+        let origin = config.origin("remotes.origin.token");
+        assert!(matches!(origin, Origin::Other(ref source) if source.contains("config.toml")));
+        let token = config.remotes["origin"].token.as_ref().unwrap();
+        assert!(matches!(
+            token.resolve(&origin).await,
+            Err(Error::UntrustedSecret(_))
+        ));
+
+        user.child("config.toml").write_str(COMMAND_TOKEN).unwrap();
+        let config = Config::load(vec![system.to_path_buf(), user.to_path_buf()])
+            .await
+            .unwrap();
+        assert_eq!(Origin::User, config.origin("remotes.origin.token"));
+    }
+
+    #[tokio::test]
+    async fn secrets_from_the_environment_are_the_users() {
+        env::set_var(
+            "TIMERS_TEST_SECRET_REMOTES__ORIGIN__URL",
+            "https://example.com",
+        );
+        env::set_var(
+            "TIMERS_TEST_SECRET_REMOTES__ORIGIN__TOKEN",
+            "{command=\"echo hi\"}",
+        );
+
+        let config = Config::load_with_env(vec![], None, "TIMERS_TEST_SECRET_")
+            .await
+            .unwrap();
+
+        assert_eq!(Origin::User, config.origin("remotes.origin.token"));
+    }
+
+    #[tokio::test]
+    async fn secrets_from_project_local_files_are_rejected() {
+        let user = TempDir::new().unwrap();
+        let local = TempDir::new().unwrap();
+        let file = local.child(local::LOCAL_FILE);
+        file.write_str(COMMAND_TOKEN).unwrap();
+
+        let result =
+            Config::load_with_local(vec![user.to_path_buf()], Some(file.to_path_buf())).await;
+
+        assert!(matches!(result, Err(Error::LocalKey(_, key)) if key == "default.remotes"));
+    }
 }
@@ -0,0 +1,29 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Per project settings, keyed by the project name.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, JsonSchema)]
+pub struct Project {
+    /// Time budget of the project in hours.
+    #[serde(default)]
+    pub budget: Budget,
+}
+
+/// Time budget of a project in hours.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, JsonSchema)]
+pub struct Budget {
+    /// Hours available over the whole lifetime of the project.
+    pub total: Option<f64>,
+    /// Hours available per calendar month.
+    pub monthly: Option<f64>,
+}
+
+impl Budget {
+    pub fn is_empty(&self) -> bool {
+        self.total.is_none() && self.monthly.is_none()
+    }
+}
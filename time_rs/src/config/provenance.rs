@@ -0,0 +1,184 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::borrow::Cow;
+
+use figment::{
+    value::{Dict, Map, Value},
+    Figment, Metadata, Profile, Provider, Source,
+};
+use serde::Serialize;
+
+use super::{Error, Result};
+
+/// Wraps a `provider` to report it as `name`, e.g. a command line flag.
+pub(crate) struct Named<P> {
+    name: Cow<'static, str>,
+    provider: P,
+}
+
+impl<P> Named<P> {
+    pub fn new<N>(name: N, provider: P) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+    {
+        Self {
+            name: name.into(),
+            provider,
+        }
+    }
+}
+
+impl<P: Provider> Provider for Named<P> {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(self.name.clone())
+    }
+
+    fn data(&self) -> std::result::Result<Map<Profile, Dict>, figment::Error> {
+        self.provider.data()
+    }
+
+    fn profile(&self) -> Option<Profile> {
+        self.provider.profile()
+    }
+}
+
+/// A single effective configuration value and where it came from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Provenance {
+    pub key: String,
+    pub value: serde_json::Value,
+    /// Name of the provider, e.g. `TOML file`.
+    pub provider: Option<String>,
+    /// The file, code location or custom source of the provider.
+    pub source: Option<String>,
+}
+
+fn describe(source: &Source) -> String {
+    match source {
+        Source::File(path) => path.display().to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn walk(figment: &Figment, key: String, value: &Value, out: &mut Vec<Provenance>) -> Result<()> {
+    if let Value::Dict(_, dict) = value {
+        for (child, value) in dict {
+            let child = match key.is_empty() {
+                true => child.clone(),
+                false => format!("{key}.{child}"),
+            };
+            walk(figment, child, value, out)?;
+        }
+
+        return Ok(());
+    }
+
+    let metadata = figment.get_metadata(value.tag());
+    out.push(Provenance {
+        key,
+        value: serde_json::to_value(value).map_err(Error::Serialize)?,
+        provider: metadata.map(|m| m.name.to_string()),
+        source: metadata.and_then(|m| m.source.as_ref()).map(describe),
+    });
+
+    Ok(())
+}
+
+/// Every leaf value below `key`, or of the whole configuration, ordered by key.
+pub fn collect(figment: &Figment, key: Option<&str>) -> Result<Vec<Provenance>> {
+    let mut out = vec![];
+
+    match key {
+        Some(key) => {
+            let value = figment
+                .find_value(key)
+                .map_err(|_| Error::UnknownKey(key.to_owned()))?;
+            walk(figment, key.to_owned(), &value, &mut out)?;
+        }
+        None => {
+            let dict = figment.extract::<Dict>().map_err(Box::new)?;
+            for key in dict.keys() {
+                let value = figment.find_value(key).map_err(Box::new)?;
+                walk(figment, key.clone(), &value, &mut out)?;
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+    use figment::providers::Serialized;
+
+    use super::*;
+    use crate::config::Config;
+
+    const TOML: &str = r#"
+    [default]
+    data_dir = "/tmp"
+
+    [default.work]
+    weekly_hours = 40
+    "#;
+
+    #[tokio::test]
+    async fn values_point_to_their_file() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("config.toml").write_str(TOML).unwrap();
+
+        let config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+        let provenance = collect(config.figment(), None).unwrap();
+
+        let weekly = provenance
+            .iter()
+            .find(|p| p.key == "work.weekly_hours")
+            .unwrap();
+        assert_eq!(serde_json::json!(40), weekly.value);
+        assert_eq!(Some("TOML file"), weekly.provider.as_deref());
+        assert!(weekly.source.as_ref().unwrap().ends_with("config.toml"));
+    }
+
+    #[tokio::test]
+    async fn named_providers_win_and_are_reported() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("config.toml").write_str(TOML).unwrap();
+
+        let mut config = Config::load(vec![tmp.to_path_buf()]).await.unwrap();
+        config.add_data_dir("/data").unwrap();
+        let provenance = collect(config.figment(), Some("data_dir")).unwrap();
+
+        assert_eq!(1, provenance.len());
+        assert_eq!(serde_json::json!("/data"), provenance[0].value);
+        assert_eq!(
+            Some("command line flag `--data-dir`"),
+            provenance[0].provider.as_deref()
+        );
+    }
+
+    #[test]
+    fn sub_keys_are_flattened() {
+        let figment = Figment::from(Named::new(
+            "test",
+            Serialized::defaults(serde_json::json!({"work": {"a": 1, "b": {"c": 2}}})),
+        ));
+
+        let keys = collect(&figment, Some("work"))
+            .unwrap()
+            .into_iter()
+            .map(|p| p.key)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["work.a", "work.b.c"], keys);
+    }
+
+    #[test]
+    fn unknown_keys_are_reported() {
+        let result = collect(&Figment::new(), Some("nope"));
+
+        assert!(matches!(result, Err(Error::UnknownKey(key)) if key == "nope"));
+    }
+}
@@ -0,0 +1,17 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::Secret;
+
+/// A remote the data repository is synced with.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+pub struct Remote {
+    /// Location of the remote repository.
+    pub url: String,
+    /// Token to authenticate with, resolved only when it is needed.
+    pub token: Option<Secret>,
+}
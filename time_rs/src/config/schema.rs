@@ -0,0 +1,49 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::collections::BTreeMap;
+
+use schemars::{schema_for, JsonSchema, Schema};
+
+use super::Config;
+
+/// Configuration of timers, every top level key is a profile, `default`
+/// applies to all of them.
+#[derive(JsonSchema)]
+#[schemars(title = "timers configuration")]
+#[allow(dead_code)]
+struct File(BTreeMap<String, Config>);
+
+/// JSON Schema of a configuration file.
+pub fn schema() -> Schema {
+    schema_for!(File)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMITTED: &str = include_str!("../../config.schema.json");
+
+    #[test]
+    fn committed_schema_is_up_to_date() {
+        let schema = serde_json::to_string_pretty(&schema()).unwrap();
+
+        assert_eq!(
+            COMMITTED.trim_end(),
+            schema,
+            "run `make schema` to update time_rs/config.schema.json"
+        );
+    }
+
+    #[test]
+    fn profiles_hold_the_config() {
+        let schema = schema();
+
+        assert_eq!(
+            Some("#/$defs/Config"),
+            schema.as_value()["additionalProperties"]["$ref"].as_str()
+        );
+    }
+}
@@ -0,0 +1,212 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{env, fmt, path::PathBuf};
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tokio::{fs, process::Command};
+
+use super::{Error, Result};
+
+/// A credential in the configuration. Apart from plain values, it can refer
+/// to the place it is kept, which is only read by [`Secret::resolve`].
+#[derive(Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Secret {
+    /// Read from a file, `~` is expanded.
+    File { file: PathBuf },
+    /// Read from an environment variable.
+    Env { env: String },
+    /// Printed by a shell command, e.g. `pass show timers`.
+    Command { command: String },
+    /// The value itself, better kept out of shared dotfiles.
+    Plain(String),
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Secret::File { file } => f.debug_struct("File").field("file", file).finish(),
+            Secret::Env { env } => f.debug_struct("Env").field("env", env).finish(),
+            Secret::Command { command } => {
+                f.debug_struct("Command").field("command", command).finish()
+            }
+            Secret::Plain(_) => f.write_str("Plain(<redacted>)"),
+        }
+    }
+}
+
+impl Secret {
+    /// Reads the value, surrounding whitespace is trimmed. Commands and files
+    /// are refused unless they come from the user, see [`Origin`].
+    pub async fn resolve(&self, origin: &Origin) -> Result<Resolved> {
+        if let (Secret::File { .. } | Secret::Command { .. }, Origin::Other(source)) =
+            (self, origin)
+        {
+            return Err(Error::UntrustedSecret(source.clone()));
+        }
+
+        let value = match self {
+            Secret::File { file } => {
+                let path = PathBuf::from(shellexpand::tilde(&file.to_string_lossy()).as_ref());
+                fs::read_to_string(&path)
+                    .await
+                    .map_err(|e| Error::SecretFile(file.clone(), e))?
+            }
+            Secret::Env { env: var } => env::var(var).map_err(|_| Error::SecretEnv(var.clone()))?,
+            Secret::Command { command } => {
+                let output = Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .output()
+                    .await
+                    .map_err(|e| Error::SecretCommandSpawn(command.clone(), e))?;
+                if !output.status.success() {
+                    return Err(Error::SecretCommand(command.clone(), output.status));
+                }
+
+                String::from_utf8(output.stdout)
+                    .map_err(|_| Error::SecretCommandOutput(command.clone()))?
+            }
+            Secret::Plain(value) => value.clone(),
+        };
+
+        Ok(Resolved(value.trim().to_string()))
+    }
+}
+
+/// Where a [`Secret`] was configured. Commands and files are only taken from
+/// the user's own configuration, other sources could make `timers` run
+/// anything or send local files to a remote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// The user config dir, or the `TIMERS_*` environment.
+    User,
+    /// Any other source, e.g. a system wide configuration file.
+    Other(String),
+}
+
+/// The value of a [`Secret`], it is never printed by `Debug`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Resolved(String);
+
+impl Resolved {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Resolved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Resolved(<redacted>)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+    use figment::{providers::Serialized, Figment};
+
+    use super::*;
+
+    const VALUE: &str = "hunter2";
+
+    fn extract(value: serde_json::Value) -> Secret {
+        Figment::from(Serialized::defaults(serde_json::json!({ "token": value })))
+            .extract_inner("token")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn plain_values_are_used_as_they_are() {
+        let secret = extract(serde_json::json!(VALUE));
+
+        assert_eq!(Secret::Plain(VALUE.to_string()), secret);
+        assert_eq!(VALUE, secret.resolve(&Origin::User).await.unwrap().expose());
+    }
+
+    #[tokio::test]
+    async fn files_are_read_and_trimmed() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("token").write_str(&format!("{VALUE}\n")).unwrap();
+
+        let secret = extract(serde_json::json!({ "file": tmp.child("token").path() }));
+
+        assert_eq!(VALUE, secret.resolve(&Origin::User).await.unwrap().expose());
+    }
+
+    #[tokio::test]
+    async fn environment_variables_are_read() {
+        env::set_var("TIMERS_TEST_SECRET", VALUE);
+
+        let secret = extract(serde_json::json!({ "env": "TIMERS_TEST_SECRET" }));
+
+        assert_eq!(VALUE, secret.resolve(&Origin::User).await.unwrap().expose());
+    }
+
+    #[tokio::test]
+    async fn commands_are_run() {
+        let secret = extract(serde_json::json!({ "command": format!("echo {VALUE}") }));
+
+        assert_eq!(VALUE, secret.resolve(&Origin::User).await.unwrap().expose());
+    }
+
+    #[tokio::test]
+    async fn failures_do_not_leak() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("token").write_str(VALUE).unwrap();
+        let token = tmp.child("token").path().display().to_string();
+
+        let secrets = [
+            Secret::Env {
+                env: "TIMERS_TEST_SECRET_MISSING".to_string(),
+            },
+            Secret::Command {
+                command: format!("cat {token}; exit 1"),
+            },
+            Secret::File {
+                file: PathBuf::from("/nonexistent/token"),
+            },
+        ];
+
+        for secret in secrets {
+            let error = secret.resolve(&Origin::User).await.unwrap_err();
+
+            assert!(!format!("{error} {error:?}").contains(VALUE));
+        }
+    }
+
+    #[tokio::test]
+    async fn debug_output_is_redacted() {
+        let secret = Secret::Plain(VALUE.to_string());
+        let resolved = secret.resolve(&Origin::User).await.unwrap();
+
+        assert!(!format!("{secret:?}").contains(VALUE));
+        assert!(!format!("{resolved:?}").contains(VALUE));
+    }
+
+    #[tokio::test]
+    async fn commands_and_files_need_to_come_from_the_user() {
+        let origin = Origin::Other("/etc/xdg/timers/config.toml".to_string());
+        let secrets = [
+            Secret::Command {
+                command: format!("echo {VALUE}"),
+            },
+            Secret::File {
+                file: PathBuf::from("/etc/passwd"),
+            },
+        ];
+
+        for secret in secrets {
+            assert!(matches!(
+                secret.resolve(&origin).await,
+                Err(Error::UntrustedSecret(source)) if source.ends_with("config.toml")
+            ));
+        }
+
+        let plain = Secret::Plain(VALUE.to_string());
+        assert_eq!(VALUE, plain.resolve(&origin).await.unwrap().expose());
+    }
+}
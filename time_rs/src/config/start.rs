@@ -0,0 +1,18 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Defaults for `timers start`, usually set by a project local `.timers.toml`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, JsonSchema)]
+pub struct StartDefaults {
+    /// Project to track the time for, unless one is given.
+    pub project: Option<String>,
+    /// Tags attached to every started entry, in addition to the given ones.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Client the work is done for, unless one is given.
+    pub client: Option<String>,
+}
@@ -0,0 +1,52 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// When the data repository is synced with its remotes in the background.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum AutoSync {
+    /// After every command that changed the time data.
+    AfterWrite,
+    /// On the first command after `interval` minutes passed since the last
+    /// sync.
+    Interval,
+    /// Only on `timers repo sync`.
+    #[default]
+    Off,
+}
+
+/// Syncing the data repository with its remotes.
+#[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
+pub struct SyncSettings {
+    /// When to sync in the background.
+    #[serde(default)]
+    pub auto: AutoSync,
+    /// Minutes between syncs with `auto = "interval"`.
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            auto: AutoSync::default(),
+            interval: default_interval(),
+        }
+    }
+}
+
+impl SyncSettings {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval * 60)
+    }
+}
+
+fn default_interval() -> u64 {
+    15
+}
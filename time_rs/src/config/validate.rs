@@ -0,0 +1,567 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use figment::{
+    providers::Serialized,
+    value::{Dict, Value},
+    Figment, Provider,
+};
+use serde::Serialize;
+use serde_json::Value as Json;
+use tokio::task;
+
+use super::{
+    discover, file_figment, is_supported,
+    local::{self, ALLOWED_KEYS},
+    schema::schema,
+    Config, Error, Result,
+};
+
+/// Upper bound of invalid values reported per profile.
+const MAX_INVALID: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Problem {
+    UnknownKey {
+        suggestion: Option<String>,
+    },
+    InvalidValue {
+        message: String,
+    },
+    Unreadable {
+        message: String,
+    },
+    /// A project local file sets more than [`ALLOWED_KEYS`].
+    NotAllowedLocally,
+    /// The file is not in a supported format and is not loaded.
+    Ignored,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::UnknownKey { suggestion: None } => f.write_str("unknown key"),
+            Problem::UnknownKey {
+                suggestion: Some(suggestion),
+            } => write!(f, "unknown key, did you mean `{suggestion}`?"),
+            Problem::InvalidValue { message } => f.write_str(message),
+            Problem::Unreadable { message } => f.write_str(message),
+            Problem::NotAllowedLocally => write!(
+                f,
+                "project local configuration may only set {}",
+                ALLOWED_KEYS.map(|k| format!("`{k}`")).join(", ")
+            ),
+            Problem::Ignored => f.write_str("ignored, not a yaml, yml, toml or json file"),
+        }
+    }
+}
+
+/// Something noteworthy about a configuration file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    /// Dotted key, starting with the profile.
+    pub key: Option<String>,
+    #[serde(flatten)]
+    pub problem: Problem,
+}
+
+impl Finding {
+    /// Ignored files are reported, but are no problem.
+    pub fn is_problem(&self) -> bool {
+        self.problem != Problem::Ignored
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+        if let Some(ref key) = self.key {
+            write!(f, ": {key}")?;
+        }
+
+        write!(f, ": {}", self.problem)
+    }
+}
+
+/// Checks every file below `dirs` and the project `local` configuration
+/// against the [schema] of the configuration.
+pub async fn validate(dirs: Vec<PathBuf>, local: Option<PathBuf>) -> Result<Vec<Finding>> {
+    task::spawn_blocking(move || {
+        let schema = schema();
+        let root = schema.as_value();
+        let profile = &root["additionalProperties"];
+
+        let shared = discover(&dirs)?.into_iter().map(|file| (file, false));
+        let local = discover(local.as_slice())?
+            .into_iter()
+            .map(|file| (file, true));
+
+        Ok(shared
+            .chain(local)
+            .flat_map(|(file, local)| validate_file(root, profile, &file, local))
+            .collect())
+    })
+    .await
+    .map_err(Error::JoinError)?
+}
+
+/// Fails for a dotted `key`, or keys in its `value`, that the [schema] of
+/// the configuration doesn't know, suggesting the closest known one.
+pub fn check_key(key: &str, value: &Value) -> Result<()> {
+    let schema = schema();
+    let root = schema.as_value();
+    let nested = key.rsplit('.').fold(value.clone(), |value, key| {
+        Value::from(Dict::from([(key.to_owned(), value)]))
+    });
+
+    let mut unknown = vec![];
+    unknown_keys(
+        root,
+        &root["additionalProperties"],
+        &nested,
+        vec![],
+        &mut unknown,
+    );
+
+    match unknown.into_iter().next() {
+        None => Ok(()),
+        Some((mut path, suggestion)) => {
+            let key = path.join(".");
+            let suggestion = suggestion.map(|suggestion| {
+                path.pop();
+                path.push(suggestion);
+                path.join(".")
+            });
+
+            Err(Error::UnknownSetting(key, suggestion))
+        }
+    }
+}
+
+fn validate_file(root: &Json, schema: &Json, file: &Path, local: bool) -> Vec<Finding> {
+    let finding = |line, key, problem| Finding {
+        file: file.to_owned(),
+        line,
+        key,
+        problem,
+    };
+
+    if !is_supported(file) {
+        return vec![finding(None, None, Problem::Ignored)];
+    }
+
+    let data = file_figment(file.to_owned(), true)
+        .map_err(|e| e.to_string())
+        .and_then(|figment| figment.data().map_err(|e| e.to_string()));
+    let data = match data {
+        Ok(data) => data,
+        Err(message) => return vec![finding(None, None, Problem::Unreadable { message })],
+    };
+    let text = fs::read_to_string(file).unwrap_or_default();
+
+    let mut findings = vec![];
+    if local {
+        for path in local::restricted(&data) {
+            findings.push(finding(
+                line_of(&text, &path),
+                Some(path.join(".")),
+                Problem::NotAllowedLocally,
+            ));
+        }
+    }
+    for (profile, dict) in data {
+        let profile = vec![profile.as_str().to_string()];
+        let mut report = |path: Vec<String>, problem| {
+            let path = [profile.clone(), path].concat();
+            findings.push(finding(
+                line_of(&text, &path),
+                Some(path.join(".")),
+                problem,
+            ));
+        };
+
+        let mut unknown = vec![];
+        unknown_keys(
+            root,
+            schema,
+            &Value::from(dict.clone()),
+            vec![],
+            &mut unknown,
+        );
+        for (path, suggestion) in unknown {
+            report(path, Problem::UnknownKey { suggestion });
+        }
+
+        for (path, message) in invalid_values(dict) {
+            report(path, Problem::InvalidValue { message });
+        }
+    }
+
+    findings
+}
+
+/// Follows references and picks the variant of `anyOf`/`oneOf` that matches
+/// the shape of `value`, e.g. the object of an `Option<Struct>`. Of several
+/// objects, the first one that knows all keys of `value` wins.
+fn resolve<'a>(root: &'a Json, schema: &'a Json, value: &Value) -> &'a Json {
+    if let Some(target) = schema.get("$ref").and_then(Json::as_str) {
+        return match root.pointer(target.trim_start_matches('#')) {
+            Some(target) => resolve(root, target, value),
+            None => schema,
+        };
+    }
+
+    let variants = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(Json::as_array);
+    let Some(variants) = variants else {
+        return schema;
+    };
+
+    let candidates = variants
+        .iter()
+        .map(|v| resolve(root, v, value))
+        .filter(|v| match value {
+            Value::Dict(..) => v
+                .get("properties")
+                .or(v.get("additionalProperties"))
+                .is_some(),
+            Value::Array(..) => v.get("items").is_some(),
+            _ => false,
+        })
+        .collect::<Vec<_>>();
+
+    let knows_all = |v: &Json| match (value, v.get("properties").and_then(Json::as_object)) {
+        (Value::Dict(_, dict), Some(properties)) => dict.keys().all(|k| properties.contains_key(k)),
+        _ => false,
+    };
+
+    candidates
+        .iter()
+        .find(|v| knows_all(v))
+        .or(candidates.first())
+        .copied()
+        .unwrap_or(schema)
+}
+
+fn unknown_keys(
+    root: &Json,
+    schema: &Json,
+    value: &Value,
+    path: Vec<String>,
+    out: &mut Vec<(Vec<String>, Option<String>)>,
+) {
+    let schema = resolve(root, schema, value);
+
+    match value {
+        Value::Dict(_, dict) => {
+            let properties = schema.get("properties").and_then(Json::as_object);
+            let additional = schema.get("additionalProperties");
+
+            for (key, value) in dict {
+                let path = [path.clone(), vec![key.clone()]].concat();
+
+                match (properties.and_then(|p| p.get(key)), additional) {
+                    (Some(schema), _) => unknown_keys(root, schema, value, path, out),
+                    (None, Some(schema)) if schema.is_object() => {
+                        unknown_keys(root, schema, value, path, out)
+                    }
+                    (None, Some(Json::Bool(true))) => {}
+                    (None, _) => {
+                        if let Some(properties) = properties {
+                            out.push((path, suggest(key, properties.keys())));
+                        }
+                    }
+                }
+            }
+        }
+        Value::Array(_, items) => {
+            if let Some(schema) = schema.get("items") {
+                for (i, value) in items.iter().enumerate() {
+                    let path = [path.clone(), vec![i.to_string()]].concat();
+                    unknown_keys(root, schema, value, path, out);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The candidate closest to `key`, if it is similar enough. Swapped or
+/// mistyped letters of short keys count as similar as well.
+fn suggest<'a>(key: &str, candidates: impl Iterator<Item = &'a String>) -> Option<String> {
+    candidates
+        .filter(|c| strsim::jaro_winkler(key, c) > 0.8 || strsim::damerau_levenshtein(key, c) <= 1)
+        .map(|c| (strsim::jaro_winkler(key, c), c))
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, c)| c.clone())
+}
+
+/// Every value that fails to deserialize. Extraction stops at the first
+/// error, so offending values are removed until the rest extracts cleanly.
+fn invalid_values(mut dict: Dict) -> Vec<(Vec<String>, String)> {
+    let mut found = vec![];
+
+    for _ in 0..MAX_INVALID {
+        let Err(errors) = Figment::from(Serialized::defaults(&dict)).extract::<Config>() else {
+            break;
+        };
+
+        let mut progress = false;
+        for error in errors {
+            progress |= remove(&mut dict, &error.path);
+            found.push((error.path.clone(), error.kind.to_string()));
+        }
+
+        if !progress {
+            break;
+        }
+    }
+
+    found
+}
+
+fn remove(dict: &mut Dict, path: &[String]) -> bool {
+    match path {
+        [] => false,
+        [key] => dict.remove(key).is_some(),
+        [key, rest @ ..] => match dict.get_mut(key) {
+            Some(Value::Dict(_, dict)) => remove(dict, rest),
+            Some(Value::Array(_, items)) => match rest {
+                [index] => index
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|i| *i < items.len())
+                    .map(|i| items.remove(i))
+                    .is_some(),
+                [index, rest @ ..] => {
+                    match index.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+                        Some(Value::Dict(_, dict)) => remove(dict, rest),
+                        _ => false,
+                    }
+                }
+                [] => false,
+            },
+            Some(_) => dict.remove(key).is_some(),
+            None => false,
+        },
+    }
+}
+
+/// Best effort line of `path` in `text`, every key is searched for at or
+/// after the line of its parent. Array indices are skipped.
+fn line_of(text: &str, path: &[String]) -> Option<usize> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let mut line = 0;
+
+    for key in path.iter().filter(|k| k.parse::<usize>().is_err()) {
+        line += lines
+            .get(line..)?
+            .iter()
+            .position(|l| contains_key(l, key))?;
+    }
+
+    Some(line + 1)
+}
+
+fn contains_key(line: &str, key: &str) -> bool {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+
+    line.match_indices(key).any(|(i, _)| {
+        let before = line[..i].chars().next_back();
+        let after = line[i + key.len()..].chars().next();
+
+        !before.is_some_and(is_word) && !after.is_some_and(is_word)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+    use rstest::*;
+
+    use super::*;
+
+    async fn check(name: &str, content: &str) -> Vec<Finding> {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(name).write_str(content).unwrap();
+
+        validate(vec![tmp.to_path_buf()], None).await.unwrap()
+    }
+
+    #[rstest]
+    #[case("data_dir", "/x", None)]
+    #[case("remotes.origin.url", "/x", None)]
+    #[case("projects.time_rs.budget.total", "10", None)]
+    #[case("data_dri", "/x", Some(("data_dri", Some("data_dir"))))]
+    #[case("remotes.origin.ulr", "/x", Some(("remotes.origin.ulr", Some("remotes.origin.url"))))]
+    #[case("work.nope", "1", Some(("work.nope", None)))]
+    #[case("work", "{weekly_horus=1}", Some(("work.weekly_horus", Some("work.weekly_hours"))))]
+    fn keys_are_checked_against_the_schema(
+        #[case] key: &str,
+        #[case] value: &str,
+        #[case] expected: Option<(&str, Option<&str>)>,
+    ) {
+        let value: Value = value.parse().unwrap_or_else(|e| match e {});
+
+        match (check_key(key, &value), expected) {
+            (Ok(()), None) => (),
+            (Err(Error::UnknownSetting(key, suggestion)), Some((expected, suggested))) => {
+                assert_eq!(expected, key);
+                assert_eq!(suggested, suggestion.as_deref());
+            }
+            (result, expected) => panic!("{expected:?} was expected, got {result:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn valid_files_have_no_findings() {
+        let findings = check(
+            "config.toml",
+            r#"
+            [default]
+            data_dir = "/tmp"
+
+            [default.work]
+            weekly_hours = 40
+
+            [[default.work.schedules]]
+            effective_from = "2026-01-01"
+            days = { monday = 8 }
+
+            [default.projects.time_rs.budget]
+            total = 100
+            "#,
+        )
+        .await;
+
+        assert_eq!(Vec::<Finding>::new(), findings);
+    }
+
+    #[rstest]
+    #[case::top_level(
+        "[default]\ndata_dri = \"/tmp\"\n",
+        "default.data_dri",
+        2,
+        Some("data_dir")
+    )]
+    #[case::nested(
+        "[default.work]\nweekly_hours = 40\nweekly_hour = 40\n",
+        "default.work.weekly_hour",
+        3,
+        Some("weekly_hours")
+    )]
+    #[case::below_map(
+        "[default.projects.time_rs.budget]\nmontly = 4\n",
+        "default.projects.time_rs.budget.montly",
+        2,
+        Some("monthly")
+    )]
+    #[case::unrelated("[default]\nfoo = 1\n", "default.foo", 2, None)]
+    #[tokio::test]
+    async fn unknown_keys_are_reported(
+        #[case] content: &str,
+        #[case] key: &str,
+        #[case] line: usize,
+        #[case] suggestion: Option<&str>,
+    ) {
+        let findings = check("config.toml", content).await;
+
+        assert_eq!(1, findings.len(), "{findings:?}");
+        assert_eq!(Some(key), findings[0].key.as_deref());
+        assert_eq!(Some(line), findings[0].line);
+        assert_eq!(
+            Problem::UnknownKey {
+                suggestion: suggestion.map(str::to_string)
+            },
+            findings[0].problem
+        );
+    }
+
+    #[rstest]
+    #[case::plain("token = \"abc\"", 0)]
+    #[case::env("token = { env = \"TOKEN\" }", 0)]
+    #[case::command("token = { command = \"pass show timers\" }", 0)]
+    #[case::unknown("token = { vault = \"timers\" }", 2)]
+    #[tokio::test]
+    async fn secrets_pick_the_matching_variant(#[case] token: &str, #[case] expected: usize) {
+        let content = format!("[default.remotes.origin]\nurl = \"https://example.com\"\n{token}\n");
+
+        let findings = check("config.toml", &content).await;
+
+        assert_eq!(expected, findings.len(), "{findings:?}");
+    }
+
+    #[tokio::test]
+    async fn every_invalid_value_is_reported() {
+        let findings = check(
+            "config.yaml",
+            "default:\n  work:\n    weekly_hours: lots\n  compliance:\n    on_stop: maybe\n",
+        )
+        .await;
+
+        let found = findings
+            .iter()
+            .map(|f| (f.key.as_deref(), f.line, f.is_problem()))
+            .collect::<Vec<_>>();
+
+        assert!(found.contains(&(Some("default.work.weekly_hours"), Some(3), true)));
+        assert!(found.contains(&(Some("default.compliance.on_stop"), Some(5), true)));
+        assert!(findings
+            .iter()
+            .all(|f| matches!(f.problem, Problem::InvalidValue { .. })));
+    }
+
+    #[tokio::test]
+    async fn unsupported_files_are_ignored() {
+        let findings = check("README.md", "# Configuration").await;
+
+        assert_eq!(1, findings.len());
+        assert_eq!(Problem::Ignored, findings[0].problem);
+        assert!(!findings[0].is_problem());
+    }
+
+    #[tokio::test]
+    async fn syntax_errors_are_reported() {
+        let findings = check("config.toml", "[default\n").await;
+
+        assert_eq!(1, findings.len());
+        assert!(matches!(findings[0].problem, Problem::Unreadable { .. }));
+    }
+
+    #[test]
+    fn keys_are_found_below_their_parents() {
+        let text = "[default]\nweekly_hours = 1\n[default.work]\nweekly_hours = 2\n";
+        let path = ["default", "work", "weekly_hours"].map(String::from);
+
+        assert_eq!(Some(4), line_of(text, &path));
+    }
+
+    #[tokio::test]
+    async fn local_files_may_only_set_start_defaults() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.child(local::LOCAL_FILE);
+        file.write_str("[default.start]\nproject = \"p\"\n[default.sync]\nauto = \"off\"\n")
+            .unwrap();
+
+        let findings = validate(vec![], Some(file.to_path_buf())).await.unwrap();
+
+        assert_eq!(1, findings.len(), "{findings:?}");
+        assert_eq!(Some("default.sync"), findings[0].key.as_deref());
+        assert_eq!(Some(3), findings[0].line);
+        assert_eq!(Problem::NotAllowedLocally, findings[0].problem);
+    }
+}
@@ -0,0 +1,172 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::path::PathBuf;
+
+use jiff::{
+    civil::{Date, Weekday},
+    SignedDuration,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::{holidays, Holiday, Result};
+
+/// Working hour targets and the flex time account.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, JsonSchema)]
+pub struct Work {
+    /// Weekly target hours, spread evenly over monday to friday.
+    pub weekly_hours: Option<f64>,
+    /// Schedules that replace `weekly_hours` from their effective date on.
+    #[serde(default)]
+    pub schedules: Vec<Schedule>,
+    /// First day that is accounted for in the balance.
+    #[schemars(with = "Option<String>", extend("format" = "date"))]
+    pub balance_start: Option<Date>,
+    /// Hours carried into the balance at `balance_start`.
+    #[serde(default)]
+    pub initial_balance: f64,
+    /// Vacation days available per year.
+    pub vacation_days: Option<f64>,
+    /// File that defines the public holidays.
+    pub holidays: Option<PathBuf>,
+}
+
+/// Working hour targets from a given day on.
+#[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
+pub struct Schedule {
+    /// First day the schedule applies to.
+    #[schemars(with = "String", extend("format" = "date"))]
+    pub effective_from: Date,
+    /// Weekly target hours, used for weekdays not listed in `days`.
+    pub weekly_hours: Option<f64>,
+    /// Target hours of single weekdays.
+    #[serde(default)]
+    pub days: Days,
+}
+
+/// Target hours per weekday.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, JsonSchema)]
+pub struct Days {
+    pub monday: Option<f64>,
+    pub tuesday: Option<f64>,
+    pub wednesday: Option<f64>,
+    pub thursday: Option<f64>,
+    pub friday: Option<f64>,
+    pub saturday: Option<f64>,
+    pub sunday: Option<f64>,
+}
+
+impl Days {
+    fn get(&self, weekday: Weekday) -> Option<f64> {
+        match weekday {
+            Weekday::Monday => self.monday,
+            Weekday::Tuesday => self.tuesday,
+            Weekday::Wednesday => self.wednesday,
+            Weekday::Thursday => self.thursday,
+            Weekday::Friday => self.friday,
+            Weekday::Saturday => self.saturday,
+            Weekday::Sunday => self.sunday,
+        }
+    }
+}
+
+fn spread_weekly(weekly_hours: f64, weekday: Weekday) -> f64 {
+    match weekday {
+        Weekday::Saturday | Weekday::Sunday => 0.0,
+        _ => weekly_hours / 5.0,
+    }
+}
+
+pub(crate) fn hours(hours: f64) -> SignedDuration {
+    SignedDuration::from_secs_f64(hours * 3600.0)
+}
+
+impl Work {
+    /// The schedule in effect on `date`, if any.
+    pub fn schedule_for(&self, date: Date) -> Option<&Schedule> {
+        self.schedules
+            .iter()
+            .filter(|s| s.effective_from <= date)
+            .max_by_key(|s| s.effective_from)
+    }
+
+    /// The target working time for `date`, not considering any absences.
+    pub fn target(&self, date: Date) -> SignedDuration {
+        let weekday = date.weekday();
+
+        let target = match self.schedule_for(date) {
+            Some(schedule) => schedule.days.get(weekday).or_else(|| {
+                schedule
+                    .weekly_hours
+                    .or(self.weekly_hours)
+                    .map(|w| spread_weekly(w, weekday))
+            }),
+            None => self.weekly_hours.map(|w| spread_weekly(w, weekday)),
+        };
+
+        hours(target.unwrap_or_default())
+    }
+
+    /// The public holidays from the configured holiday file.
+    pub async fn holidays(&self) -> Result<Vec<Holiday>> {
+        match self.holidays {
+            Some(ref file) => holidays::load(file.clone()).await,
+            None => Ok(vec![]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::civil::date;
+    use rstest::{fixture, rstest};
+
+    use super::*;
+
+    #[fixture]
+    fn work() -> Work {
+        Work {
+            weekly_hours: Some(40.0),
+            schedules: vec![
+                Schedule {
+                    effective_from: date(2026, 2, 1),
+                    weekly_hours: Some(30.0),
+                    days: Days {
+                        friday: Some(0.0),
+                        ..Default::default()
+                    },
+                },
+                Schedule {
+                    effective_from: date(2026, 3, 1),
+                    weekly_hours: None,
+                    days: Days {
+                        saturday: Some(2.0),
+                        ..Default::default()
+                    },
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case::before_schedules(date(2026, 1, 5), 8.0)]
+    #[case::weekend(date(2026, 1, 10), 0.0)]
+    #[case::weekly_from_schedule(date(2026, 2, 2), 6.0)]
+    #[case::day_from_schedule(date(2026, 2, 6), 0.0)]
+    #[case::fallback_to_global_weekly(date(2026, 3, 2), 8.0)]
+    #[case::explicit_weekend(date(2026, 3, 7), 2.0)]
+    fn target_follows_schedules(work: Work, #[case] day: Date, #[case] expected: f64) {
+        assert_eq!(hours(expected), work.target(day));
+    }
+
+    #[test]
+    fn no_target_without_configuration() {
+        assert_eq!(
+            SignedDuration::ZERO,
+            Work::default().target(date(2026, 1, 5))
+        );
+    }
+}
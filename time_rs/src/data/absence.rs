@@ -0,0 +1,141 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{fmt, path::PathBuf, str::FromStr};
+
+use clap::ValueEnum;
+use jiff::{civil::Date, ToSpan};
+use serde::{Deserialize, Serialize};
+
+use super::Error;
+
+/// Folder inside the data repository that holds days off.
+pub const ABSENCES_DIR: &str = "absences";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum AbsenceKind {
+    Vacation,
+    Sick,
+    Other,
+}
+
+impl fmt::Display for AbsenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            AbsenceKind::Vacation => "vacation",
+            AbsenceKind::Sick => "sick",
+            AbsenceKind::Other => "other",
+        };
+
+        f.write_str(kind)
+    }
+}
+
+/// A range of days off, both ends are inclusive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Absence {
+    #[serde(skip)]
+    pub id: String,
+    pub kind: AbsenceKind,
+    pub from: Date,
+    pub to: Date,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl Absence {
+    pub fn new(kind: AbsenceKind, range: DateRange, note: Option<String>) -> Self {
+        Self {
+            id: format!("{}-{}", range.from, kind),
+            kind,
+            from: range.from,
+            to: range.to,
+            note,
+        }
+    }
+
+    /// Path of the absence file relative to the repository root.
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(ABSENCES_DIR).join(format!("{}.toml", self.id))
+    }
+
+    pub fn contains(&self, date: Date) -> bool {
+        self.from <= date && date <= self.to
+    }
+
+    /// Every day covered by the absence.
+    pub fn days(&self) -> impl Iterator<Item = Date> + '_ {
+        self.from.series(1.day()).take_while(|d| *d <= self.to)
+    }
+}
+
+/// An inclusive range of days, written as `2026-12-24..2026-12-31` or as a
+/// single day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub from: Date,
+    pub to: Date,
+}
+
+impl FromStr for DateRange {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidRange(s.to_owned());
+
+        let (from, to) = s.split_once("..").unwrap_or((s, s));
+        let from: Date = from.trim().parse().map_err(|_| invalid())?;
+        let to: Date = to.trim().parse().map_err(|_| invalid())?;
+
+        if from > to {
+            return Err(invalid());
+        }
+
+        Ok(Self { from, to })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::civil::date;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("2026-12-24..2026-12-31", date(2026, 12, 24), date(2026, 12, 31))]
+    #[case("2026-12-24", date(2026, 12, 24), date(2026, 12, 24))]
+    #[case("2026-12-24 .. 2026-12-24", date(2026, 12, 24), date(2026, 12, 24))]
+    fn parses_ranges(#[case] input: &str, #[case] from: Date, #[case] to: Date) {
+        assert_eq!(DateRange { from, to }, input.parse().unwrap());
+    }
+
+    #[rstest]
+    #[case("2026-12-31..2026-12-24")]
+    #[case("2026-12-24..")]
+    #[case("tomorrow")]
+    fn rejects_invalid_ranges(#[case] input: &str) {
+        assert!(matches!(
+            input.parse::<DateRange>(),
+            Err(Error::InvalidRange(_))
+        ));
+    }
+
+    #[test]
+    fn id_and_days_follow_the_range() {
+        let absence = Absence::new(
+            AbsenceKind::Vacation,
+            "2026-12-30..2027-01-02".parse().unwrap(),
+            None,
+        );
+
+        assert_eq!("2026-12-30-vacation", absence.id);
+        assert_eq!(
+            PathBuf::from("absences/2026-12-30-vacation.toml"),
+            absence.path()
+        );
+        assert_eq!(4, absence.days().count());
+    }
+}
@@ -0,0 +1,149 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::path::PathBuf;
+
+use jiff::{civil::Date, tz::TimeZone, SignedDuration, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Folder inside the data repository that holds the time entries.
+pub const ENTRIES_DIR: &str = "entries";
+
+/// A single tracked time span.
+///
+/// Entries are stored one per file below [`ENTRIES_DIR`], the file name is
+/// the entry id, which is derived from the start time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    #[serde(skip)]
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Client the work is done for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub start: Timestamp,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<Timestamp>,
+}
+
+impl Entry {
+    pub fn new(project: Option<String>, tags: Vec<String>, start: Timestamp) -> Self {
+        Self {
+            id: Self::id_for(start),
+            project,
+            client: None,
+            tags,
+            start,
+            end: None,
+        }
+    }
+
+    /// The id of an entry started at `start`.
+    pub fn id_for(start: Timestamp) -> String {
+        start.strftime("%Y%m%dT%H%M%SZ").to_string()
+    }
+
+    /// Path of the entry file relative to the repository root.
+    pub fn path(&self) -> PathBuf {
+        let year = self.id.get(0..4).unwrap_or("0000");
+        let month = self.id.get(4..6).unwrap_or("00");
+
+        PathBuf::from(ENTRIES_DIR)
+            .join(year)
+            .join(month)
+            .join(format!("{}.toml", self.id))
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.end.is_none()
+    }
+
+    /// The tracked duration, running entries are measured until `now`.
+    pub fn duration(&self, now: Timestamp) -> SignedDuration {
+        self.end.unwrap_or(now).duration_since(self.start)
+    }
+
+    /// The part of the entry that falls onto the local `date`.
+    pub fn duration_on(&self, date: Date, tz: &TimeZone, now: Timestamp) -> SignedDuration {
+        let (Ok(day_start), Ok(day_end)) = (
+            date.to_zoned(tz.clone()),
+            date.tomorrow().and_then(|d| d.to_zoned(tz.clone())),
+        ) else {
+            return SignedDuration::ZERO;
+        };
+
+        let start = self.start.max(day_start.timestamp());
+        let end = self.end.unwrap_or(now).min(day_end.timestamp());
+
+        if end > start {
+            end.duration_since(start)
+        } else {
+            SignedDuration::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::civil::date;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn entry(start: &str, end: Option<&str>) -> Entry {
+        let mut entry = Entry::new(None, vec![], start.parse().unwrap());
+        entry.end = end.map(|e| e.parse().unwrap());
+        entry
+    }
+
+    #[test]
+    fn path_is_derived_from_start() {
+        let entry = entry("2026-10-19T08:30:00Z", None);
+
+        assert_eq!("20261019T083000Z", entry.id);
+        assert_eq!(
+            PathBuf::from("entries/2026/10/20261019T083000Z.toml"),
+            entry.path()
+        );
+    }
+
+    #[rstest]
+    #[case(date(2026, 10, 19), 3600 * 2)]
+    #[case(date(2026, 10, 20), 3600)]
+    #[case(date(2026, 10, 21), 0)]
+    fn duration_is_split_at_midnight(#[case] day: Date, #[case] seconds: i64) {
+        let entry = entry("2026-10-19T22:00:00Z", Some("2026-10-20T01:00:00Z"));
+        let now = "2026-10-22T00:00:00Z".parse().unwrap();
+
+        assert_eq!(
+            SignedDuration::from_secs(seconds),
+            entry.duration_on(day, &TimeZone::UTC, now)
+        );
+    }
+
+    #[test]
+    fn running_entry_is_measured_until_now() {
+        let entry = entry("2026-10-19T08:00:00Z", None);
+        let now = "2026-10-19T09:30:00Z".parse().unwrap();
+
+        assert!(entry.is_running());
+        assert_eq!(SignedDuration::from_mins(90), entry.duration(now));
+    }
+
+    #[test]
+    fn roundtrips_through_toml() {
+        let mut entry = entry("2026-10-19T08:00:00Z", Some("2026-10-19T12:00:00Z"));
+        entry.project = Some("time_rs".to_string());
+        entry.tags = vec!["dev".to_string()];
+
+        let content = toml::to_string(&entry).unwrap();
+        let mut parsed: Entry = toml::from_str(&content).unwrap();
+        parsed.id = entry.id.clone();
+
+        assert_eq!(entry, parsed);
+    }
+}
@@ -0,0 +1,69 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+use std::{io::Error as IoError, path::PathBuf};
+
+use glob::{GlobError, PatternError};
+use thiserror::Error as ThisError;
+use tokio::task::JoinError;
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error("no datadir specified")]
+    NoDataDir,
+    #[error("input/output error")]
+    Io(#[from] IoError),
+    #[error("{:?} can't be converted to string", .0)]
+    PathStringConversion(PathBuf),
+    #[error("invalid glob pattern: {}", .0)]
+    PatternError(String, #[source] PatternError),
+    #[error("glob pattern matching failed")]
+    GlobError(#[from] GlobError),
+    #[error("{:?} is not a valid record", .0)]
+    Parse(PathBuf, #[source] toml::de::Error),
+    #[error("'{}' is not a valid date range", .0)]
+    InvalidRange(String),
+    #[error("record {} already exists", .0)]
+    Exists(String),
+    #[error("record {} does not exist", .0)]
+    NotFound(String),
+    #[error("record can not be serialized")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("failed to open the data repository at {:?}", .0)]
+    GixOpen(PathBuf, #[source] Box<gix::open::Error>),
+    #[error("failed to commit to the data repository")]
+    GixCommit(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("failed to read the history of the data repository")]
+    GixHistory(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("the data repository at {:?} has uncommitted changes", .0)]
+    Dirty(PathBuf),
+    #[error("there is nothing to undo")]
+    NothingToUndo,
+    #[error("there is nothing to redo")]
+    NothingToRedo,
+    #[error("{:?} changed again after {}, it can not be reverted", .1, .0)]
+    Conflict(gix::ObjectId, PathBuf),
+    #[error("{:?} does not look like a timers data repository", .0)]
+    NotADataRepo(PathBuf),
+    #[error("{:?} does not hold a valid format version", .0)]
+    InvalidFormat(PathBuf),
+    #[error("the data repository uses format {}, but this version of timers only supports up to {}, please upgrade timers", .0, .1)]
+    UnsupportedFormat(u32, u32),
+    #[error("both sides changed {:?} differently", .0)]
+    MergeConflict(Vec<String>),
+    #[error("another timers process is changing the data, if there is none remove {:?}", .0)]
+    Locked(PathBuf),
+    #[error("there are no remotes to sync with")]
+    NoRemotes,
+    #[error("failed to run git, it is needed to sync")]
+    GitSpawn(#[source] IoError),
+    #[error("git {} failed: {}", .0, .1)]
+    Git(String, String),
+    #[error("{} and this repository changed {} in ways that can't be merged, the merge was aborted", .0, .1.join(", "))]
+    SyncConflict(String, Vec<String>),
+    #[error("interrupted, the data was left as it was")]
+    Interrupted,
+    #[error("couldn't join work units")]
+    JoinError(#[source] JoinError),
+}
@@ -0,0 +1,179 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Version of the on-disk layout of the data repository and the migrations
+//! between versions.
+//!
+//! The version is stored in [`FORMAT_FILE`] at the root of the repository.
+//! Repositories created before the file existed are version 0. Whenever the
+//! layout changes, [`VERSION`] is bumped and a migration appended to
+//! [`MIGRATIONS`].
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{apply, record, undo, Change, Error, Result};
+
+/// File at the root of the repository holding the format version.
+pub const FORMAT_FILE: &str = "FORMAT";
+
+/// Format version written and understood by this version of timers.
+pub const VERSION: u32 = 1;
+
+/// A step from one format version to the next.
+struct Migration {
+    /// What the step does, listed in the commit message.
+    description: &'static str,
+    /// Computes the changes of the step from the worktree, which already has
+    /// the earlier steps applied.
+    changes: fn(&Path) -> Result<Vec<Change>>,
+}
+
+/// `MIGRATIONS[n]` upgrades from version `n` to `n + 1`.
+const MIGRATIONS: [Migration; VERSION as usize] = [Migration {
+    description: "add the FORMAT file",
+    changes: |_| Ok(vec![]),
+}];
+
+/// The change that writes `version` to [`FORMAT_FILE`].
+pub fn change(version: u32) -> Change {
+    Change::Write(FORMAT_FILE.into(), format!("{version}\n"))
+}
+
+/// The format version of the repository at `root`, `None` if there is no
+/// repository yet.
+pub fn read(root: &Path) -> Result<Option<u32>> {
+    if !root.join(".git").exists() {
+        return Ok(None);
+    }
+
+    let file = root.join(FORMAT_FILE);
+    match fs::read_to_string(&file) {
+        Ok(content) => content
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| Error::InvalidFormat(file)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Some(0)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Upgrades the repository at `root` to [`VERSION`] in a single commit.
+/// Returns the versions it was migrated from and to, `None` if there is no
+/// repository or it is up to date already.
+pub(crate) fn migrate(root: &Path) -> Result<Option<(u32, u32)>> {
+    let Some(found) = read(root)? else {
+        return Ok(None);
+    };
+    if found > VERSION {
+        return Err(Error::UnsupportedFormat(found, VERSION));
+    }
+    if found == VERSION {
+        return Ok(None);
+    }
+
+    let repo = undo::open(root)?;
+    if repo.head_id().is_ok() {
+        undo::ensure_clean(&repo, root)?;
+    }
+
+    let mut changes = BTreeMap::<PathBuf, Change>::new();
+    let mut message = format!("migrate the data repository from format {found} to {VERSION}\n\n");
+    for migration in &MIGRATIONS[found as usize..] {
+        let step = (migration.changes)(root)?;
+        apply(root, &step)?;
+        changes.extend(step.into_iter().map(|c| (path(&c).to_owned(), c)));
+        message.push_str(&format!("- {}\n", migration.description));
+    }
+
+    let format = change(VERSION);
+    apply(root, std::slice::from_ref(&format))?;
+    changes.insert(FORMAT_FILE.into(), format);
+
+    record(root, &message, &changes.into_values().collect::<Vec<_>>())?;
+
+    Ok(Some((found, VERSION)))
+}
+
+fn path(change: &Change) -> &Path {
+    match change {
+        Change::Write(path, _) | Change::Remove(path) => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+    use crate::data::{tests::init_store, Store};
+
+    #[test]
+    fn missing_repos_have_no_format() {
+        let tmp = TempDir::new().unwrap();
+
+        assert_eq!(None, read(tmp.path()).unwrap());
+        assert_eq!(None, migrate(tmp.path()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn old_repos_are_migrated_in_one_commit() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        store
+            .commit("first", vec![Change::Write("a.toml".into(), "".into())])
+            .await
+            .unwrap();
+
+        assert_eq!(Some(0), read(store.root()).unwrap());
+        assert_eq!(Some((0, VERSION)), store.migrate().await.unwrap());
+        assert_eq!(None, store.migrate().await.unwrap());
+
+        let history = store.history(None).await.unwrap();
+        assert_eq!(2, history.len());
+        assert!(history[0]
+            .message
+            .starts_with("migrate the data repository"));
+        tmp.child("repo/FORMAT").assert(format!("{VERSION}\n"));
+        let repo = gix::open(store.root()).unwrap();
+        assert!(!repo.is_dirty().unwrap());
+    }
+
+    #[test]
+    fn empty_repos_are_migrated() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+
+        assert_eq!(Some((0, VERSION)), migrate(store.root()).unwrap());
+        assert_eq!(Some(VERSION), read(store.root()).unwrap());
+    }
+
+    #[test]
+    fn newer_formats_are_refused() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        tmp.child("repo/FORMAT")
+            .write_str(&format!("{}\n", VERSION + 1))
+            .unwrap();
+
+        assert!(matches!(
+            migrate(store.root()),
+            Err(Error::UnsupportedFormat(found, VERSION)) if found == VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn garbage_formats_are_refused() {
+        let tmp = TempDir::new().unwrap();
+        let store = Store::new(tmp.path());
+        tmp.child(".git").create_dir_all().unwrap();
+        tmp.child("FORMAT").write_str("one").unwrap();
+
+        assert!(matches!(read(store.root()), Err(Error::InvalidFormat(_))));
+    }
+}
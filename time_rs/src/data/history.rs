@@ -0,0 +1,468 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Changes of the time entries, read from the commits of the data repository.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use gix::{
+    bstr::{BString, ByteSlice},
+    object::tree::diff::ChangeDetached,
+    revision::walk::Sorting,
+    traverse::{commit::simple::CommitTimeOrder, tree::Recorder},
+    ObjectId, Repository,
+};
+use jiff::Timestamp;
+
+use super::{entry::ENTRIES_DIR, Entry, Error, Result};
+
+/// A single commit of the data repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Revision {
+    pub id: ObjectId,
+    pub author: String,
+    /// When the change was authored.
+    pub time: Timestamp,
+    /// First line of the commit message.
+    pub message: String,
+    /// Changed entries, ordered by their id.
+    pub changes: Vec<EntryChange>,
+}
+
+/// How a commit changed a single entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryChange {
+    Added(Entry),
+    Changed {
+        id: String,
+        fields: Vec<FieldChange>,
+    },
+    Removed(Entry),
+}
+
+impl EntryChange {
+    pub fn id(&self) -> &str {
+        match self {
+            EntryChange::Added(entry) | EntryChange::Removed(entry) => &entry.id,
+            EntryChange::Changed { id, .. } => id,
+        }
+    }
+}
+
+/// A field of an entry before and after a commit, missing fields are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+pub(crate) fn history_error<E>(e: E) -> Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    Error::GixHistory(Box::new(e))
+}
+
+/// Every commit reachable from `HEAD`, newest first. Merges are compared to
+/// their first parent. With `since`, the walk stops at the commits made
+/// before it.
+pub(crate) fn revisions(root: &Path, since: Option<Timestamp>) -> Result<Vec<Revision>> {
+    let repo = gix::open(root).map_err(|e| Error::GixOpen(root.to_owned(), Box::new(e)))?;
+    let Some(head) = repo.head_id().ok() else {
+        return Ok(vec![]);
+    };
+    let sorting = match since {
+        Some(since) => Sorting::ByCommitTimeCutoff {
+            order: CommitTimeOrder::NewestFirst,
+            seconds: since.as_second(),
+        },
+        None => Sorting::BreadthFirst,
+    };
+
+    let revisions = head
+        .ancestors()
+        .sorting(sorting)
+        .all()
+        .map_err(history_error)?
+        .map(|info| {
+            let info = info.map_err(history_error)?;
+            let commit = info.object().map_err(history_error)?;
+            let author = commit.author().map_err(history_error)?;
+            let time = author.time().map_err(history_error)?;
+            let time = Timestamp::from_second(time.seconds).map_err(history_error)?;
+            // the cutoff is by commit time, rebased commits can be authored earlier
+            if since.is_some_and(|since| time < since) {
+                return Ok(None);
+            }
+            let message = commit.message().map_err(history_error)?;
+            let (before, after) =
+                changed_entries(&repo, info.parent_ids.first().copied(), info.id)?;
+
+            Ok(Some(Revision {
+                id: info.id,
+                author: author.name.to_str_lossy().into_owned(),
+                time,
+                message: message.summary().to_str_lossy().into_owned(),
+                changes: changes(&repo, &before, &after)?,
+            }))
+        })
+        .filter_map(Result::transpose)
+        .collect();
+
+    revisions
+}
+
+/// The blobs below `dir` in the tree of `commit`, all of them without a
+/// `dir`, keyed by their path.
+pub(crate) fn blobs(
+    repo: &Repository,
+    commit: Option<ObjectId>,
+    dir: Option<&str>,
+) -> Result<BTreeMap<PathBuf, ObjectId>> {
+    let Some(commit) = commit else {
+        return Ok(BTreeMap::new());
+    };
+
+    let mut tree = repo
+        .find_commit(commit)
+        .map_err(history_error)?
+        .tree()
+        .map_err(history_error)?;
+    if let Some(dir) = dir {
+        let Some(entry) = tree.lookup_entry_by_path(dir).map_err(history_error)? else {
+            return Ok(BTreeMap::new());
+        };
+        tree = entry
+            .object()
+            .map_err(history_error)?
+            .peel_to_tree()
+            .map_err(history_error)?;
+    }
+
+    let mut recorder = Recorder::default();
+    tree.traverse()
+        .breadthfirst(&mut recorder)
+        .map_err(history_error)?;
+
+    Ok(recorder
+        .records
+        .into_iter()
+        .filter(|r| r.mode.is_blob())
+        .map(|r| {
+            let path = r.filepath.to_path_lossy();
+            let path = dir.map_or_else(|| path.to_path_buf(), |dir| Path::new(dir).join(&path));
+
+            (path, r.oid)
+        })
+        .collect())
+}
+
+/// The files `commit` changed compared to `parent`. Subtrees that are the
+/// same in both are skipped without reading them.
+pub(crate) fn changed_paths(
+    repo: &Repository,
+    parent: Option<ObjectId>,
+    commit: ObjectId,
+) -> Result<Vec<PathBuf>> {
+    Ok(diff(repo, parent, commit)?
+        .iter()
+        .filter(|change| change.entry_mode().is_blob())
+        .map(|change| change.location().to_path_lossy().into_owned())
+        .collect())
+}
+
+/// The blobs of the entries `commit` changed compared to `parent`, before
+/// and after the commit, keyed by their path.
+fn changed_entries(
+    repo: &Repository,
+    parent: Option<ObjectId>,
+    commit: ObjectId,
+) -> Result<(BTreeMap<PathBuf, ObjectId>, BTreeMap<PathBuf, ObjectId>)> {
+    let mut before = BTreeMap::new();
+    let mut after = BTreeMap::new();
+    let entry = |location: &BString| {
+        let path = location.to_path_lossy().into_owned();
+        path.starts_with(ENTRIES_DIR).then_some(path)
+    };
+
+    for change in diff(repo, parent, commit)? {
+        match change {
+            ChangeDetached::Addition {
+                location,
+                entry_mode,
+                id,
+                ..
+            } if entry_mode.is_blob() => {
+                after.extend(entry(&location).map(|path| (path, id)));
+            }
+            ChangeDetached::Deletion {
+                location,
+                entry_mode,
+                id,
+                ..
+            } if entry_mode.is_blob() => {
+                before.extend(entry(&location).map(|path| (path, id)));
+            }
+            ChangeDetached::Modification {
+                location,
+                previous_entry_mode,
+                previous_id,
+                entry_mode,
+                id,
+            } => {
+                if previous_entry_mode.is_blob() {
+                    before.extend(entry(&location).map(|path| (path, previous_id)));
+                }
+                if entry_mode.is_blob() {
+                    after.extend(entry(&location).map(|path| (path, id)));
+                }
+            }
+            ChangeDetached::Rewrite {
+                source_location,
+                source_entry_mode,
+                source_id,
+                location,
+                entry_mode,
+                id,
+                ..
+            } => {
+                if source_entry_mode.is_blob() {
+                    before.extend(entry(&source_location).map(|path| (path, source_id)));
+                }
+                if entry_mode.is_blob() {
+                    after.extend(entry(&location).map(|path| (path, id)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok((before, after))
+}
+
+/// The changes between the trees of `parent` and `commit`, subtrees that
+/// are the same in both are skipped without reading them.
+fn diff(
+    repo: &Repository,
+    parent: Option<ObjectId>,
+    commit: ObjectId,
+) -> Result<Vec<ChangeDetached>> {
+    let tree = |id: ObjectId| {
+        repo.find_commit(id)
+            .map_err(history_error)?
+            .tree()
+            .map_err(history_error)
+    };
+    let before = parent.map(tree).transpose()?;
+    let after = tree(commit)?;
+
+    repo.diff_tree_to_tree(before.as_ref(), &after, gix::diff::Options::default())
+        .map_err(history_error)
+}
+
+fn changes(
+    repo: &Repository,
+    before: &BTreeMap<PathBuf, ObjectId>,
+    after: &BTreeMap<PathBuf, ObjectId>,
+) -> Result<Vec<EntryChange>> {
+    let mut changes = vec![];
+
+    let records = before
+        .keys()
+        .chain(after.keys())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect::<BTreeSet<_>>();
+
+    for path in records {
+        let change = match (before.get(path), after.get(path)) {
+            (Some(old), Some(new)) if old == new => continue,
+            (Some(old), Some(new)) => {
+                let old = read_entry(repo, path, *old)?;
+                let new = read_entry(repo, path, *new)?;
+
+                EntryChange::Changed {
+                    fields: fields(&old, &new)?,
+                    id: new.id,
+                }
+            }
+            (None, Some(new)) => EntryChange::Added(read_entry(repo, path, *new)?),
+            (Some(old), None) => EntryChange::Removed(read_entry(repo, path, *old)?),
+            (None, None) => continue,
+        };
+        changes.push(change);
+    }
+
+    changes.sort_by(|a, b| a.id().cmp(b.id()));
+
+    Ok(changes)
+}
+
+fn read_entry(repo: &Repository, path: &Path, blob: ObjectId) -> Result<Entry> {
+    let blob = repo.find_blob(blob).map_err(history_error)?;
+    let content = blob.data.to_str_lossy();
+    let entry: Entry = toml::from_str(&content).map_err(|e| Error::Parse(path.to_owned(), e))?;
+    let id = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| Error::PathStringConversion(path.to_owned()))?
+        .to_owned();
+
+    Ok(Entry { id, ..entry })
+}
+
+/// The fields that differ between `old` and `new`, ordered by name.
+fn fields(old: &Entry, new: &Entry) -> Result<Vec<FieldChange>> {
+    let old = toml::Table::try_from(old)?;
+    let new = toml::Table::try_from(new)?;
+
+    Ok(old
+        .keys()
+        .chain(new.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|field| old.get(*field) != new.get(*field))
+        .map(|field| FieldChange {
+            field: field.clone(),
+            old: old.get(field).map(display),
+            new: new.get(field).map(display),
+        })
+        .collect())
+}
+
+fn display(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Array(values) => values.iter().map(display).collect::<Vec<_>>().join(", "),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::TempDir;
+    use jiff::SignedDuration;
+
+    use super::*;
+    use crate::data::{tests::init_store, Change};
+
+    fn entry(start: &str) -> Entry {
+        Entry::new(Some("time_rs".to_string()), vec![], start.parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn empty_repositories_have_no_history() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+
+        assert!(store.history(None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn commits_show_their_entry_changes() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        let first = entry("2026-10-19T08:00:00Z");
+        let second = entry("2026-10-20T08:00:00Z");
+        let stopped = Entry {
+            end: Some("2026-10-19T12:00:00Z".parse().unwrap()),
+            project: None,
+            ..first.clone()
+        };
+
+        store
+            .commit("start", vec![Change::record(first.path(), &first).unwrap()])
+            .await
+            .unwrap();
+        store
+            .commit(
+                "edit",
+                vec![
+                    Change::record(stopped.path(), &stopped).unwrap(),
+                    Change::record(second.path(), &second).unwrap(),
+                ],
+            )
+            .await
+            .unwrap();
+        store
+            .commit(
+                "budgets",
+                vec![Change::Write("budgets.toml".into(), "".into())],
+            )
+            .await
+            .unwrap();
+        store
+            .commit("remove", vec![Change::Remove(second.path())])
+            .await
+            .unwrap();
+
+        let history = store.history(None).await.unwrap();
+
+        let messages = history
+            .iter()
+            .map(|r| r.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["remove", "budgets", "edit", "start"], messages);
+        assert_eq!(
+            vec![EntryChange::Removed(second.clone())],
+            history[0].changes
+        );
+        assert!(history[1].changes.is_empty());
+        assert_eq!(
+            vec![
+                EntryChange::Changed {
+                    id: first.id.clone(),
+                    fields: vec![
+                        FieldChange {
+                            field: "end".to_string(),
+                            old: None,
+                            new: Some("2026-10-19T12:00:00Z".to_string()),
+                        },
+                        FieldChange {
+                            field: "project".to_string(),
+                            old: Some("time_rs".to_string()),
+                            new: None,
+                        },
+                    ],
+                },
+                EntryChange::Added(second),
+            ],
+            history[2].changes
+        );
+        assert_eq!(vec![EntryChange::Added(first)], history[3].changes);
+    }
+
+    #[tokio::test]
+    async fn the_walk_stops_at_since() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        let first = entry("2026-10-19T08:00:00Z");
+        let second = entry("2026-10-20T08:00:00Z");
+        for entry in [&first, &second] {
+            store
+                .commit("start", vec![Change::record(entry.path(), entry).unwrap()])
+                .await
+                .unwrap();
+        }
+        let now = Timestamp::now();
+
+        let all = store.history(None).await.unwrap();
+        let since = store
+            .history(Some(now - SignedDuration::from_hours(1)))
+            .await
+            .unwrap();
+        let later = store
+            .history(Some(now + SignedDuration::from_hours(1)))
+            .await
+            .unwrap();
+
+        assert_eq!(all, since);
+        assert_eq!(vec![EntryChange::Added(second)], since[0].changes);
+        assert_eq!(vec![EntryChange::Added(first)], since[1].changes);
+        assert!(later.is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! The files every data repository starts with.
+
+use std::{fs, io, path::Path};
+
+use super::{
+    absence::ABSENCES_DIR, entry::ENTRIES_DIR, format, Change, Error, Result, BUDGETS_FILE,
+};
+
+/// Attributes of the records, assigning them the [`MERGE_DRIVER`].
+pub const GITATTRIBUTES_FILE: &str = ".gitattributes";
+
+/// Explains the repository to people browsing it.
+pub const README_FILE: &str = "README.md";
+
+/// Name of the git merge driver for records, see [`super::merge`].
+pub const MERGE_DRIVER: &str = "timers";
+
+/// Keeps otherwise empty folders in git.
+const KEEP_FILE: &str = ".gitkeep";
+
+const README: &str = "\
+# Time data
+
+This repository holds time entries and absences recorded by `timers`.
+
+- `entries/YYYY/MM/<id>.toml` are the time entries
+- `absences/YYYY/<id>.toml` are vacations, sick days and other absences
+- `budgets.toml` holds the project budgets shared by everyone
+- `FORMAT` is the version of this layout
+
+Please use `timers` to change the data, it keeps the files consistent.
+";
+
+/// Lines of [`GITATTRIBUTES_FILE`].
+fn attributes() -> Vec<String> {
+    [ENTRIES_DIR, ABSENCES_DIR]
+        .into_iter()
+        .map(|dir| format!("{dir}/**/*.toml merge={MERGE_DRIVER}"))
+        .collect()
+}
+
+/// The changes adding the parts of the layout missing in `root`. Existing
+/// files are kept, existing attributes are extended. The format is only
+/// written if there are no records yet, older data gets migrated instead.
+pub fn seed(root: &Path) -> Result<Vec<Change>> {
+    let mut changes = vec![];
+    let fresh = [ENTRIES_DIR, ABSENCES_DIR]
+        .iter()
+        .all(|dir| !root.join(dir).exists());
+
+    for dir in [ENTRIES_DIR, ABSENCES_DIR] {
+        if !root.join(dir).exists() {
+            changes.push(Change::Write(Path::new(dir).join(KEEP_FILE), String::new()));
+        }
+    }
+
+    let mut gitattributes = read(&root.join(GITATTRIBUTES_FILE))?;
+    let missing = attributes()
+        .into_iter()
+        .filter(|line| !gitattributes.lines().any(|l| l.trim() == line))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        if !gitattributes.is_empty() && !gitattributes.ends_with('\n') {
+            gitattributes.push('\n');
+        }
+        missing
+            .iter()
+            .for_each(|line| gitattributes.push_str(&format!("{line}\n")));
+        changes.push(Change::Write(GITATTRIBUTES_FILE.into(), gitattributes));
+    }
+
+    if !root.join(README_FILE).exists() {
+        changes.push(Change::Write(README_FILE.into(), README.to_owned()));
+    }
+
+    if fresh && !root.join(format::FORMAT_FILE).exists() {
+        changes.push(format::change(format::VERSION));
+    }
+
+    Ok(changes)
+}
+
+/// Checks that `root` holds time data in a format this version understands.
+/// A repository without any part of the layout is most likely something
+/// else entirely.
+pub fn validate(root: &Path) -> Result<()> {
+    let known = [format::FORMAT_FILE, ENTRIES_DIR, ABSENCES_DIR, BUDGETS_FILE]
+        .iter()
+        .any(|path| root.join(path).exists());
+    if !known {
+        return Err(Error::NotADataRepo(root.to_owned()));
+    }
+
+    match format::read(root)? {
+        Some(found) if found > format::VERSION => {
+            Err(Error::UnsupportedFormat(found, format::VERSION))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn read(file: &Path) -> Result<String> {
+    match fs::read_to_string(file) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+
+    fn paths(changes: &[Change]) -> Vec<PathBuf> {
+        changes
+            .iter()
+            .map(|c| match c {
+                Change::Write(path, _) | Change::Remove(path) => path.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn empty_repos_get_the_full_layout() {
+        let tmp = TempDir::new().unwrap();
+
+        let changes = seed(tmp.path()).unwrap();
+
+        assert_eq!(
+            vec![
+                PathBuf::from("entries/.gitkeep"),
+                PathBuf::from("absences/.gitkeep"),
+                PathBuf::from(GITATTRIBUTES_FILE),
+                PathBuf::from(README_FILE),
+                PathBuf::from(format::FORMAT_FILE),
+            ],
+            paths(&changes)
+        );
+    }
+
+    #[test]
+    fn existing_files_are_kept() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("entries/2026/10/a.toml").touch().unwrap();
+        tmp.child(README_FILE).write_str("# Mine").unwrap();
+        tmp.child(GITATTRIBUTES_FILE)
+            .write_str("*.png binary")
+            .unwrap();
+
+        let changes = seed(tmp.path()).unwrap();
+
+        assert_eq!(
+            vec![
+                Change::Write("absences/.gitkeep".into(), String::new()),
+                Change::Write(
+                    GITATTRIBUTES_FILE.into(),
+                    "*.png binary\n\
+                     entries/**/*.toml merge=timers\n\
+                     absences/**/*.toml merge=timers\n"
+                        .into()
+                ),
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn complete_layouts_need_no_changes() {
+        let tmp = TempDir::new().unwrap();
+        for change in seed(tmp.path()).unwrap() {
+            if let Change::Write(path, content) = change {
+                tmp.child(path).write_str(&content).unwrap();
+            }
+        }
+
+        assert_eq!(Vec::<Change>::new(), seed(tmp.path()).unwrap());
+    }
+
+    #[test]
+    fn unrelated_repos_are_not_valid() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(".git").create_dir_all().unwrap();
+        tmp.child("Cargo.toml").touch().unwrap();
+
+        assert!(matches!(validate(tmp.path()), Err(Error::NotADataRepo(_))));
+
+        tmp.child("entries/2026/10/a.toml").touch().unwrap();
+        assert!(validate(tmp.path()).is_ok());
+
+        tmp.child("FORMAT").write_str("999").unwrap();
+        assert!(matches!(
+            validate(tmp.path()),
+            Err(Error::UnsupportedFormat(999, _))
+        ));
+    }
+}
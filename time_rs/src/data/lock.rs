@@ -0,0 +1,327 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Advisory lock keeping concurrent `timers` processes from changing the data
+//! dir at the same time.
+//!
+//! The lock is a file in the data dir holding the PID of its owner. It lives
+//! next to the repository rather than inside of it, as `repo clone`,
+//! `destroy` and `restore` replace the repository as a whole. A lock whose
+//! owner died is taken over, so a crash never needs manual cleanup. The same
+//! goes for a lock that never got its PID written, once it is older than the
+//! timeout.
+//!
+//! Taking over moves the stale lock away first and checks it again after the
+//! move. Waiters that saw the same dead owner would otherwise remove the lock
+//! that the first of them created in the meantime.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use tokio::{fs, io::AsyncWriteExt, time::Instant};
+
+use super::{Error, Result};
+
+/// Name of the lock file in the data dir.
+pub const LOCK_FILE: &str = "timers.lock";
+
+/// How often a waiting process checks the lock again.
+const POLL: Duration = Duration::from_millis(100);
+
+/// Lock files without a PID are left alone for at least this long, their
+/// owner may still be writing it.
+const MIN_STALE_AGE: Duration = Duration::from_secs(1);
+
+/// A held lock of the data dir, released when dropped.
+#[derive(Debug)]
+pub struct Lock {
+    file: PathBuf,
+}
+
+impl Lock {
+    /// Locks `data_dir`, waiting up to `timeout` for other processes to
+    /// release it. A zero `timeout` gives up immediately, as does setting
+    /// `interrupt` while waiting.
+    pub async fn acquire(
+        data_dir: &Path,
+        timeout: Duration,
+        interrupt: &AtomicBool,
+    ) -> Result<Self> {
+        fs::create_dir_all(data_dir).await?;
+        let file = data_dir.join(LOCK_FILE);
+        let deadline = Instant::now() + timeout;
+        let stale_age = timeout.max(MIN_STALE_AGE);
+
+        loop {
+            match create(&file).await {
+                Ok(()) => return Ok(Self { file }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => (),
+                Err(e) => return Err(e.into()),
+            }
+
+            if stale(&file, stale_age).await? {
+                take_over(&file, stale_age).await?;
+                continue;
+            }
+
+            if interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            if Instant::now() >= deadline {
+                return Err(Error::Locked(file));
+            }
+            tokio::time::sleep(POLL).await;
+        }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.file);
+    }
+}
+
+async fn create(file: &Path) -> io::Result<()> {
+    let mut lock = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(file)
+        .await?;
+    lock.write_all(format!("{}\n", std::process::id()).as_bytes())
+        .await?;
+    lock.flush().await
+}
+
+/// Whether the owner of the lock `file` died, or never wrote its PID and the
+/// file is older than `stale_age`. Vanished locks aren't stale.
+async fn stale(file: &Path, stale_age: Duration) -> Result<bool> {
+    let content = match fs::read_to_string(file).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    if let Ok(pid) = content.trim().parse() {
+        return Ok(!alive(pid));
+    }
+
+    let modified = match fs::metadata(file).await {
+        Ok(metadata) => metadata.modified()?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(SystemTime::now()
+        .duration_since(modified)
+        .is_ok_and(|age| age >= stale_age))
+}
+
+/// Removes the stale lock `file`. It is moved to a name of its own first,
+/// a lock that turns out to be fresh after the move was created by another
+/// waiter in the meantime, and is put back unless yet another one exists.
+async fn take_over(file: &Path, stale_age: Duration) -> Result<()> {
+    static TAKEOVERS: AtomicUsize = AtomicUsize::new(0);
+
+    let mut moved = file.as_os_str().to_owned();
+    moved.push(format!(
+        ".{}-{}",
+        std::process::id(),
+        TAKEOVERS.fetch_add(1, Ordering::Relaxed)
+    ));
+    let moved = PathBuf::from(moved);
+
+    match fs::rename(file, &moved).await {
+        Ok(()) => (),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    }
+    if !stale(&moved, stale_age).await? {
+        match fs::hard_link(&moved, file).await {
+            Err(e) if e.kind() != io::ErrorKind::AlreadyExists => return Err(e.into()),
+            _ => (),
+        }
+    }
+    fs::remove_file(&moved).await?;
+
+    Ok(())
+}
+
+/// Whether the process `pid` still runs. Processes of other users can't be
+/// signalled, but exist nonetheless.
+#[cfg(unix)]
+fn alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+
+    // SAFETY: signal 0 only checks whether the process exists
+    #[allow(unsafe_code)]
+    let result = unsafe { libc::kill(pid, 0) };
+
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Without a way to check, every owner is considered alive.
+#[cfg(not(unix))]
+fn alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+
+    const WAIT: Duration = Duration::from_millis(300);
+
+    static NO_INTERRUPT: AtomicBool = AtomicBool::new(false);
+
+    #[tokio::test]
+    async fn the_lock_is_released_on_drop() {
+        let tmp = TempDir::new().unwrap();
+
+        let lock = Lock::acquire(tmp.path(), Duration::ZERO, &NO_INTERRUPT)
+            .await
+            .unwrap();
+        tmp.child(LOCK_FILE)
+            .assert(format!("{}\n", std::process::id()));
+
+        drop(lock);
+        assert!(!tmp.child(LOCK_FILE).path().exists());
+        Lock::acquire(tmp.path(), Duration::ZERO, &NO_INTERRUPT)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn held_locks_time_out() {
+        let tmp = TempDir::new().unwrap();
+        let _lock = Lock::acquire(tmp.path(), Duration::ZERO, &NO_INTERRUPT)
+            .await
+            .unwrap();
+
+        let started = Instant::now();
+        let result = Lock::acquire(tmp.path(), WAIT, &NO_INTERRUPT).await;
+
+        assert!(matches!(result, Err(Error::Locked(_))));
+        assert!(started.elapsed() >= WAIT);
+    }
+
+    #[tokio::test]
+    async fn released_locks_are_taken_while_waiting() {
+        let tmp = TempDir::new().unwrap();
+        let lock = Lock::acquire(tmp.path(), Duration::ZERO, &NO_INTERRUPT)
+            .await
+            .unwrap();
+
+        let waiting = Lock::acquire(tmp.path(), Duration::from_secs(10), &NO_INTERRUPT);
+        let release = async {
+            tokio::time::sleep(WAIT).await;
+            drop(lock);
+        };
+        let (result, ()) = tokio::join!(waiting, release);
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn no_wait_fails_immediately() {
+        let tmp = TempDir::new().unwrap();
+        let _lock = Lock::acquire(tmp.path(), Duration::ZERO, &NO_INTERRUPT)
+            .await
+            .unwrap();
+
+        let result = Lock::acquire(tmp.path(), Duration::ZERO, &NO_INTERRUPT).await;
+
+        assert!(matches!(result, Err(Error::Locked(file)) if file == tmp.child(LOCK_FILE).path()));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn locks_of_dead_processes_are_taken_over() {
+        let tmp = TempDir::new().unwrap();
+        // PIDs are limited to 2^22 on Linux and lower elsewhere
+        tmp.child(LOCK_FILE).write_str("99999999\n").unwrap();
+
+        let _lock = Lock::acquire(tmp.path(), Duration::ZERO, &NO_INTERRUPT)
+            .await
+            .unwrap();
+
+        tmp.child(LOCK_FILE)
+            .assert(format!("{}\n", std::process::id()));
+    }
+
+    #[tokio::test]
+    async fn unreadable_locks_are_respected() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(LOCK_FILE).touch().unwrap();
+
+        let result = Lock::acquire(tmp.path(), Duration::ZERO, &NO_INTERRUPT).await;
+
+        assert!(matches!(result, Err(Error::Locked(_))));
+    }
+
+    #[tokio::test]
+    async fn unreadable_locks_older_than_the_timeout_are_taken_over() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(LOCK_FILE).touch().unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(tmp.child(LOCK_FILE))
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+
+        let short = Lock::acquire(tmp.path(), Duration::from_secs(120), &NO_INTERRUPT);
+        let result = tokio::time::timeout(WAIT, short).await;
+        assert!(result.is_err(), "the lock is younger than the timeout");
+
+        let _lock = Lock::acquire(tmp.path(), Duration::from_secs(30), &NO_INTERRUPT)
+            .await
+            .unwrap();
+        tmp.child(LOCK_FILE)
+            .assert(format!("{}\n", std::process::id()));
+    }
+
+    #[tokio::test]
+    async fn fresh_locks_survive_a_late_take_over() {
+        let tmp = TempDir::new().unwrap();
+        let _lock = Lock::acquire(tmp.path(), Duration::ZERO, &NO_INTERRUPT)
+            .await
+            .unwrap();
+
+        // a waiter that saw the previous owner dead, after the lock changed
+        take_over(tmp.child(LOCK_FILE).path(), MIN_STALE_AGE)
+            .await
+            .unwrap();
+
+        tmp.child(LOCK_FILE)
+            .assert(format!("{}\n", std::process::id()));
+        assert_eq!(1, std::fs::read_dir(&tmp).unwrap().count());
+    }
+
+    #[tokio::test]
+    async fn interrupts_stop_the_wait() {
+        let tmp = TempDir::new().unwrap();
+        let _lock = Lock::acquire(tmp.path(), Duration::ZERO, &NO_INTERRUPT)
+            .await
+            .unwrap();
+        let interrupt = AtomicBool::new(false);
+
+        let waiting = Lock::acquire(tmp.path(), Duration::from_secs(10), &interrupt);
+        let press = async {
+            tokio::time::sleep(WAIT).await;
+            interrupt.store(true, Ordering::Relaxed);
+        };
+        let started = Instant::now();
+        let (result, ()) = tokio::join!(waiting, press);
+
+        assert!(matches!(result, Err(Error::Interrupted)));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,132 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Three-way merge of records, used as git merge driver.
+//!
+//! Records are merged field by field: a field changed on one side only takes
+//! that change, a field changed the same way on both sides is taken as well.
+//! Only fields changed differently on both sides conflict.
+
+use std::{collections::BTreeSet, fs, path::Path};
+
+use toml::{Table, Value};
+
+use super::{Error, Result};
+
+/// Merges the records in `base`, `ours` and `theirs` into `ours`, as git
+/// expects from a merge driver. `ours` is left alone on conflicts.
+pub fn files(base: &Path, ours: &Path, theirs: &Path) -> Result<()> {
+    let merged = tables(&read(base)?, &read(ours)?, &read(theirs)?)?;
+
+    fs::write(ours, toml::to_string(&merged)?)?;
+
+    Ok(())
+}
+
+/// Merges the fields of `ours` and `theirs`, based on `base`.
+pub fn tables(base: &Table, ours: &Table, theirs: &Table) -> Result<Table> {
+    let keys = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .collect::<BTreeSet<_>>();
+
+    let mut merged = Table::new();
+    let mut conflicts = vec![];
+
+    for key in keys {
+        match field(base.get(key), ours.get(key), theirs.get(key)) {
+            Ok(Some(value)) => {
+                merged.insert(key.clone(), value.clone());
+            }
+            Ok(None) => (),
+            Err(()) => conflicts.push(key.clone()),
+        }
+    }
+
+    match conflicts.is_empty() {
+        true => Ok(merged),
+        false => Err(Error::MergeConflict(conflicts)),
+    }
+}
+
+fn field<'a>(
+    base: Option<&'a Value>,
+    ours: Option<&'a Value>,
+    theirs: Option<&'a Value>,
+) -> std::result::Result<Option<&'a Value>, ()> {
+    if ours == theirs || theirs == base {
+        Ok(ours)
+    } else if ours == base {
+        Ok(theirs)
+    } else {
+        Err(())
+    }
+}
+
+/// Reads the record in `file`, git passes an empty base if both sides added
+/// the file, which reads as a record without fields.
+fn read(file: &Path) -> Result<Table> {
+    let content = fs::read_to_string(file)?;
+
+    toml::from_str(&content).map_err(|e| Error::Parse(file.to_owned(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+
+    fn table(content: &str) -> Table {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn changes_of_both_sides_are_combined() {
+        let base = table("start = 1\nproject = \"a\"\ntags = [\"x\"]");
+        let ours = table("start = 1\nproject = \"b\"\ntags = [\"x\"]");
+        let theirs = table("start = 1\nproject = \"a\"\ntags = [\"x\"]\nend = 2");
+
+        assert_eq!(
+            table("start = 1\nproject = \"b\"\ntags = [\"x\"]\nend = 2"),
+            tables(&base, &ours, &theirs).unwrap()
+        );
+    }
+
+    #[test]
+    fn removed_fields_stay_removed() {
+        let base = table("start = 1\nend = 2");
+        let ours = table("start = 1");
+
+        assert_eq!(table("start = 1"), tables(&base, &ours, &base).unwrap());
+    }
+
+    #[test]
+    fn different_changes_conflict() {
+        let base = table("start = 1\nend = 2");
+        let ours = table("start = 2\nend = 3");
+        let theirs = table("start = 0\nend = 4");
+
+        assert!(matches!(
+            tables(&base, &ours, &theirs),
+            Err(Error::MergeConflict(keys)) if keys == ["end", "start"]
+        ));
+    }
+
+    #[test]
+    fn the_result_is_written_to_ours() {
+        let tmp = TempDir::new().unwrap();
+        let base = tmp.child("base");
+        let ours = tmp.child("ours");
+        let theirs = tmp.child("theirs");
+        base.touch().unwrap();
+        ours.write_str("start = 1\n").unwrap();
+        theirs.write_str("end = 2\n").unwrap();
+
+        files(&base, &ours, &theirs).unwrap();
+
+        ours.assert("end = 2\nstart = 1\n");
+    }
+}
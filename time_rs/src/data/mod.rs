@@ -0,0 +1,414 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Access to the time data stored in the git repository below the data dir.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use gix::{objs::tree::EntryKind, ObjectId};
+use jiff::Timestamp;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::task;
+
+pub use self::{
+    absence::{Absence, AbsenceKind, DateRange},
+    entry::Entry,
+    error::Error,
+    history::{EntryChange, FieldChange, Revision},
+    lock::Lock,
+    sync::{State as SyncState, Target},
+};
+use crate::config::{Budget, Config};
+
+pub mod absence;
+pub mod entry;
+pub mod error;
+pub mod format;
+pub mod history;
+pub mod layout;
+pub mod lock;
+pub mod merge;
+pub mod sync;
+pub mod undo;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Folder below the data dir that holds the git repository.
+pub const REPO_DIR: &str = "repo";
+
+/// File inside the data repository that holds project budgets.
+pub const BUDGETS_FILE: &str = "budgets.toml";
+
+/// A single modification of the repository worktree, paths are relative to
+/// the repository root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Write(PathBuf, String),
+    Remove(PathBuf),
+}
+
+impl Change {
+    /// Writes the serialized `record` to `path`.
+    pub fn record<T>(path: PathBuf, record: &T) -> Result<Self>
+    where
+        T: Serialize,
+    {
+        Ok(Self::Write(path, toml::to_string(record)?))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    pub fn new<P>(root: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self { root: root.into() }
+    }
+
+    /// Opens the store in the repository below the configured data dir.
+    pub fn open(config: &Config) -> Result<Self> {
+        let data_dir = config.data_dir.as_ref().ok_or(Error::NoDataDir)?;
+
+        Ok(Self::new(data_dir.join(REPO_DIR)))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// All entries, ordered by their start time.
+    pub async fn entries(&self) -> Result<Vec<Entry>> {
+        let mut entries = self
+            .read_records::<Entry>(entry::ENTRIES_DIR)
+            .await?
+            .into_iter()
+            .map(|(id, entry)| Entry { id, ..entry })
+            .collect::<Vec<_>>();
+
+        entries.sort_by_key(|e| e.start);
+
+        Ok(entries)
+    }
+
+    /// The currently running entry, if there is any.
+    pub async fn running(&self) -> Result<Option<Entry>> {
+        Ok(self.entries().await?.into_iter().rfind(Entry::is_running))
+    }
+
+    /// All absences, ordered by their first day.
+    pub async fn absences(&self) -> Result<Vec<Absence>> {
+        let mut absences = self
+            .read_records::<Absence>(absence::ABSENCES_DIR)
+            .await?
+            .into_iter()
+            .map(|(id, absence)| Absence { id, ..absence })
+            .collect::<Vec<_>>();
+
+        absences.sort_by_key(|a| a.from);
+
+        Ok(absences)
+    }
+
+    /// Budgets shared through the repository, keyed by project.
+    pub async fn budgets(&self) -> Result<BTreeMap<String, Budget>> {
+        let file = self.root.join(BUDGETS_FILE);
+
+        match tokio::fs::read_to_string(&file).await {
+            Ok(content) => toml::from_str(&content).map_err(|e| Error::Parse(file, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Every commit of the repository, newest first. With `since`, only the
+    /// ones made at or after it.
+    pub async fn history(&self, since: Option<Timestamp>) -> Result<Vec<Revision>> {
+        let root = self.root.clone();
+
+        task::spawn_blocking(move || history::revisions(&root, since))
+            .await
+            .map_err(Error::JoinError)?
+    }
+
+    /// Reverts the last `count` changes, see [`undo`].
+    pub async fn undo(&self, count: usize) -> Result<Vec<ObjectId>> {
+        let root = self.root.clone();
+
+        task::spawn_blocking(move || undo::undo(&root, count))
+            .await
+            .map_err(Error::JoinError)?
+    }
+
+    /// Reapplies the last `count` undone changes, see [`undo`].
+    pub async fn redo(&self, count: usize) -> Result<Vec<ObjectId>> {
+        let root = self.root.clone();
+
+        task::spawn_blocking(move || undo::redo(&root, count))
+            .await
+            .map_err(Error::JoinError)?
+    }
+
+    /// Upgrades the repository to the current [`format`], returns the
+    /// versions it was migrated from and to, if it had to be.
+    pub async fn migrate(&self) -> Result<Option<(u32, u32)>> {
+        let root = self.root.clone();
+
+        task::spawn_blocking(move || format::migrate(&root))
+            .await
+            .map_err(Error::JoinError)?
+    }
+
+    /// Applies `changes` to the worktree and records them as a single commit.
+    pub async fn commit<M>(&self, message: M, changes: Vec<Change>) -> Result<ObjectId>
+    where
+        M: Into<String>,
+    {
+        let root = self.root.clone();
+        let message = message.into();
+
+        task::spawn_blocking(move || commit_changes(&root, &message, &changes))
+            .await
+            .map_err(Error::JoinError)?
+    }
+
+    async fn read_records<T>(&self, dir: &str) -> Result<Vec<(String, T)>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let pattern = self.root.join(dir).join("**").join("*.toml");
+
+        task::spawn_blocking(move || {
+            let pattern = pattern
+                .to_str()
+                .ok_or_else(|| Error::PathStringConversion(pattern.clone()))?;
+
+            glob::glob(pattern)
+                .map_err(|e| Error::PatternError(pattern.to_owned(), e))?
+                .map(|file| read_record(&file?))
+                .collect()
+        })
+        .await
+        .map_err(Error::JoinError)?
+    }
+
+    /// Every entry file, along with the entry or why it can not be read.
+    pub async fn entry_files(&self) -> Result<Vec<(PathBuf, Result<Entry>)>> {
+        let pattern = self.root.join(entry::ENTRIES_DIR).join("**").join("*.toml");
+
+        task::spawn_blocking(move || {
+            let pattern = pattern
+                .to_str()
+                .ok_or_else(|| Error::PathStringConversion(pattern.clone()))?;
+
+            glob::glob(pattern)
+                .map_err(|e| Error::PatternError(pattern.to_owned(), e))?
+                .map(|file| {
+                    let file = file?;
+                    let entry =
+                        read_record::<Entry>(&file).map(|(id, entry)| Entry { id, ..entry });
+
+                    Ok((file, entry))
+                })
+                .collect()
+        })
+        .await
+        .map_err(Error::JoinError)?
+    }
+}
+
+/// Reads the record in `file`, its id is the file name.
+fn read_record<T>(file: &Path) -> Result<(String, T)>
+where
+    T: DeserializeOwned,
+{
+    let id = file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| Error::PathStringConversion(file.to_owned()))?
+        .to_owned();
+    let content = fs::read_to_string(file)?;
+    let record = toml::from_str(&content).map_err(|e| Error::Parse(file.to_owned(), e))?;
+
+    Ok((id, record))
+}
+
+fn commit_error<E>(e: E) -> Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    Error::GixCommit(Box::new(e))
+}
+
+fn commit_changes(root: &Path, message: &str, changes: &[Change]) -> Result<ObjectId> {
+    apply(root, changes)?;
+
+    record(root, message, changes)
+}
+
+/// Applies `changes` to the worktree, without committing them.
+fn apply(root: &Path, changes: &[Change]) -> Result<()> {
+    for change in changes {
+        match change {
+            Change::Write(path, content) => {
+                let file = root.join(path);
+                if let Some(parent) = file.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(file, content)?;
+            }
+            Change::Remove(path) => fs::remove_file(root.join(path))?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Commits `changes`, that have been applied to the worktree already.
+fn record(root: &Path, message: &str, changes: &[Change]) -> Result<ObjectId> {
+    let mut repo = gix::open(root).map_err(|e| Error::GixOpen(root.to_owned(), Box::new(e)))?;
+    let signature = repo
+        .committer_or_set_generic_fallback()
+        .map_err(commit_error)?
+        .to_owned()
+        .map_err(commit_error)?;
+
+    let head_tree = repo.head_tree_id_or_empty().map_err(commit_error)?;
+    let mut editor = repo.edit_tree(head_tree).map_err(commit_error)?;
+    for change in changes {
+        match change {
+            Change::Write(path, content) => {
+                let blob = repo.write_blob(content).map_err(commit_error)?;
+                editor
+                    .upsert(rela_path(path).as_ref(), EntryKind::Blob, blob)
+                    .map_err(commit_error)?;
+            }
+            Change::Remove(path) => {
+                editor
+                    .remove(rela_path(path).as_ref())
+                    .map_err(commit_error)?;
+            }
+        }
+    }
+    let tree = editor.write().map_err(commit_error)?.detach();
+
+    let parents = repo.head_id().ok().map(|id| id.detach());
+    let mut time = gix::date::parse::TimeBuf::default();
+    let signature = signature.to_ref(&mut time);
+    let commit = repo
+        .commit_as(signature, signature, "HEAD", message, tree, parents)
+        .map_err(commit_error)?
+        .detach();
+
+    let mut index = repo.index_from_tree(&tree).map_err(commit_error)?;
+    index.write(Default::default()).map_err(commit_error)?;
+
+    Ok(commit)
+}
+
+fn rela_path(path: &Path) -> std::borrow::Cow<'_, gix::bstr::BStr> {
+    gix::path::to_unix_separators_on_windows(gix::path::into_bstr(path))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use assert_fs::{prelude::*, TempDir};
+    use gix::create::{Kind, Options};
+
+    use super::*;
+
+    /// Creates an empty data repository inside of `tmp`.
+    pub(crate) fn init_store(tmp: &TempDir) -> Store {
+        let root = tmp.child(REPO_DIR);
+        root.create_dir_all().unwrap();
+        gix::ThreadSafeRepository::init(root.path(), Kind::WithWorktree, Options::default())
+            .unwrap();
+
+        Store::new(root.path())
+    }
+
+    #[tokio::test]
+    async fn missing_folders_have_no_records() {
+        let tmp = TempDir::new().unwrap();
+        let store = Store::new(tmp.path());
+
+        assert!(store.entries().await.unwrap().is_empty());
+        assert!(store.absences().await.unwrap().is_empty());
+        assert!(store.budgets().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn committed_entries_can_be_read() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        let entry = Entry::new(
+            Some("time_rs".to_string()),
+            vec![],
+            "2026-10-19T08:00:00Z".parse().unwrap(),
+        );
+
+        store
+            .commit("start", vec![Change::record(entry.path(), &entry).unwrap()])
+            .await
+            .unwrap();
+
+        assert_eq!(vec![entry.clone()], store.entries().await.unwrap());
+        assert_eq!(Some(entry), store.running().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn commits_are_chained() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+
+        let first = store
+            .commit("first", vec![Change::Write("a.toml".into(), "".into())])
+            .await
+            .unwrap();
+        let second = store
+            .commit("second", vec![Change::Remove("a.toml".into())])
+            .await
+            .unwrap();
+
+        let repo = gix::open(store.root()).unwrap();
+        let commit = repo.find_commit(second).unwrap();
+
+        assert_eq!(vec![first], commit.parent_ids().collect::<Vec<_>>());
+        assert!(!tmp.child("repo/a.toml").exists());
+        assert!(!repo.is_dirty().unwrap());
+    }
+
+    #[tokio::test]
+    async fn budgets_are_read_from_the_repository() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child(BUDGETS_FILE)
+            .write_str("[time_rs]\ntotal = 100\n")
+            .unwrap();
+        let store = Store::new(tmp.path());
+
+        let budgets = store.budgets().await.unwrap();
+
+        assert_eq!(Some(100.0), budgets["time_rs"].total);
+        assert_eq!(None, budgets["time_rs"].monthly);
+    }
+
+    #[tokio::test]
+    async fn broken_records_are_reported() {
+        let tmp = TempDir::new().unwrap();
+        tmp.child("absences/broken.toml")
+            .write_str("kind = 1")
+            .unwrap();
+        let store = Store::new(tmp.path());
+
+        assert!(matches!(store.absences().await, Err(Error::Parse(_, _))));
+    }
+}
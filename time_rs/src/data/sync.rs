@@ -0,0 +1,513 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Syncing the data repository with its remotes, and the outcome of syncs
+//! that ran in the background.
+//!
+//! `gix` can fetch, but neither push nor run the merge driver of the records,
+//! so syncing runs the `git` executable. Each remote is fetched into
+//! `refs/remotes/<name>/*`, the branch of the same name is merged into the
+//! current one and the result is pushed back.
+
+use std::{
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+    process::Output,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+use tokio::{fs, process::Command};
+
+use super::{Error, Result, Store};
+
+/// File in the data dir keeping the [`State`] of background syncs.
+pub const STATE_FILE: &str = "sync.toml";
+
+/// Failures kept in the [`State`], older ones are dropped.
+const MAX_FAILURES: usize = 10;
+
+/// A remote to sync with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target {
+    /// Name of the remote, used for its tracking refs.
+    pub name: String,
+    /// Location of the remote repository.
+    pub url: String,
+    /// Sent as bearer token to remotes accessed via HTTP.
+    pub token: Option<String>,
+}
+
+/// A background sync that failed.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Failure {
+    pub at: Timestamp,
+    pub error: String,
+}
+
+/// What happened to the background syncs so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct State {
+    /// When the last sync started, whether it succeeded or not.
+    pub attempted: Option<Timestamp>,
+    /// Failures since the last successful sync, oldest first.
+    #[serde(default)]
+    pub failures: Vec<Failure>,
+}
+
+impl State {
+    /// Reads the state from `data_dir`, a missing file is an empty state.
+    pub async fn load(data_dir: &Path) -> Result<Self> {
+        let file = data_dir.join(STATE_FILE);
+        match fs::read_to_string(&file).await {
+            Ok(content) => toml::from_str(&content).map_err(|e| Error::Parse(file, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes the state to `data_dir`, replacing the file at once, so readers
+    /// never see half of it.
+    pub async fn save(&self, data_dir: &Path) -> Result<()> {
+        let file = data_dir.join(STATE_FILE);
+        let partial = file.with_extension("toml.partial");
+        fs::write(&partial, toml::to_string(self)?).await?;
+        fs::rename(partial, file).await?;
+
+        Ok(())
+    }
+
+    /// Notes a sync starting `at`.
+    pub fn attempt(&mut self, at: Timestamp) {
+        self.attempted = Some(at);
+    }
+
+    /// Notes the outcome of the sync, a success clears the failures.
+    pub fn finish<E>(&mut self, at: Timestamp, result: &std::result::Result<(), E>)
+    where
+        E: std::error::Error,
+    {
+        match result {
+            Ok(()) => self.failures.clear(),
+            Err(e) => {
+                self.failures.push(Failure {
+                    at,
+                    error: chain(e),
+                });
+                let dropped = self.failures.len().saturating_sub(MAX_FAILURES);
+                self.failures.drain(..dropped);
+            }
+        }
+    }
+}
+
+impl Store {
+    /// Fetches from every target, merges its branch of the same name as the
+    /// current one and pushes the result. `interrupt` is checked between
+    /// the steps, a merge in progress is aborted.
+    pub async fn sync(&self, targets: &[Target], interrupt: &AtomicBool) -> Result<()> {
+        if targets.is_empty() {
+            return Err(Error::NoRemotes);
+        }
+
+        let git = Git::new(self.root())?;
+        let branch = git.run(["symbolic-ref", "--short", "HEAD"]).await?;
+
+        for target in targets {
+            git.sync(target, &branch, interrupt)
+                .await
+                .map_err(|e| or_interrupted(interrupt, e))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// [`Error::Interrupted`] if `interrupt` is set, `error` otherwise. git gets
+/// the Ctrl-C as well and fails because of it.
+fn or_interrupted(interrupt: &AtomicBool, error: Error) -> Error {
+    match interrupt.load(Ordering::Relaxed) {
+        true => Error::Interrupted,
+        false => error,
+    }
+}
+
+/// Fails with [`Error::Interrupted`] if `interrupt` is set.
+fn check_interrupt(interrupt: &AtomicBool) -> Result<()> {
+    match interrupt.load(Ordering::Relaxed) {
+        true => Err(Error::Interrupted),
+        false => Ok(()),
+    }
+}
+
+/// Runs `git` in a repository, as the same person `timers` commits as.
+struct Git {
+    root: PathBuf,
+    identity: [(&'static str, String); 4],
+}
+
+impl Git {
+    fn new(root: &Path) -> Result<Self> {
+        let mut repo = gix::open(root).map_err(|e| Error::GixOpen(root.to_owned(), Box::new(e)))?;
+        let signature = repo
+            .committer_or_set_generic_fallback()
+            .map_err(|e| Error::GixCommit(Box::new(e)))?;
+        let name = signature.name.to_string();
+        let email = signature.email.to_string();
+
+        Ok(Self {
+            root: root.to_owned(),
+            identity: [
+                ("GIT_AUTHOR_NAME", name.clone()),
+                ("GIT_AUTHOR_EMAIL", email.clone()),
+                ("GIT_COMMITTER_NAME", name),
+                ("GIT_COMMITTER_EMAIL", email),
+            ],
+        })
+    }
+
+    async fn sync(&self, target: &Target, branch: &str, interrupt: &AtomicBool) -> Result<()> {
+        let token = target
+            .token
+            .as_ref()
+            .map(|token| format!("Authorization: Bearer {token}"));
+        let tracking = format!("refs/remotes/{}/{branch}", target.name);
+
+        check_interrupt(interrupt)?;
+        let fetch = format!("+refs/heads/*:refs/remotes/{}/*", target.name);
+        self.remote(["fetch", "--prune", &target.url, &fetch], token.as_deref())
+            .await?;
+
+        check_interrupt(interrupt)?;
+        if self.exists(&tracking).await? {
+            let merge = self.output(["merge", "--no-edit", &tracking]).await?;
+            if !merge.status.success() {
+                let conflicts = self.conflicts().await?;
+                self.output(["merge", "--abort"]).await?;
+                return Err(match conflicts.is_empty() {
+                    true => failed(&["merge", &tracking], &merge),
+                    false => Error::SyncConflict(target.name.clone(), conflicts),
+                });
+            }
+        }
+
+        check_interrupt(interrupt)?;
+        if self.exists("HEAD").await? {
+            let push = format!("HEAD:refs/heads/{branch}");
+            self.remote(["push", &target.url, &push], token.as_deref())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// The files a merge left unmerged.
+    async fn conflicts(&self) -> Result<Vec<String>> {
+        let unmerged = self.run(["diff", "--name-only", "--diff-filter=U"]).await?;
+
+        Ok(unmerged.lines().map(str::to_owned).collect())
+    }
+
+    /// Whether `rev` names a commit.
+    async fn exists(&self, rev: &str) -> Result<bool> {
+        let output = self
+            .output(["rev-parse", "--verify", "--quiet", rev])
+            .await?;
+
+        Ok(output.status.success())
+    }
+
+    /// Runs a command talking to a remote, passing the token through the
+    /// environment, so it doesn't show up in the process list.
+    async fn remote<const N: usize>(&self, args: [&str; N], token: Option<&str>) -> Result<()> {
+        let mut command = self.command(args);
+        if let Some(header) = token {
+            command
+                .env("GIT_CONFIG_COUNT", "1")
+                .env("GIT_CONFIG_KEY_0", "http.extraHeader")
+                .env("GIT_CONFIG_VALUE_0", header);
+        }
+        let output = command.output().await.map_err(Error::GitSpawn)?;
+
+        check(&args, output).map(|_| ())
+    }
+
+    /// Runs a command and returns its trimmed output.
+    async fn run<const N: usize>(&self, args: [&str; N]) -> Result<String> {
+        let output = self.output(args).await?;
+
+        check(&args, output)
+    }
+
+    async fn output<I, S>(&self, args: I) -> Result<Output>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.command(args).output().await.map_err(Error::GitSpawn)
+    }
+
+    fn command<I, S>(&self, args: I) -> Command
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut command = Command::new("git");
+        command
+            .arg("-C")
+            .arg(&self.root)
+            .args(args)
+            .envs(self.identity.iter().map(|(k, v)| (k, v)))
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(std::process::Stdio::null());
+
+        command
+    }
+}
+
+fn check(args: &[&str], output: Output) -> Result<String> {
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned()),
+        false => Err(failed(args, &output)),
+    }
+}
+
+/// The error of a failed git command, with its output on a single line.
+/// Some commands, like `merge`, report on stdout instead of stderr.
+fn failed(args: &[&str], output: &Output) -> Error {
+    let line = |bytes: &[u8]| {
+        String::from_utf8_lossy(bytes)
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let message = match line(&output.stderr) {
+        stderr if stderr.is_empty() => line(&output.stdout),
+        stderr => stderr,
+    };
+
+    Error::Git(args.join(" "), message)
+}
+
+/// The error and its sources in a single line.
+fn chain(e: &dyn std::error::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(e) = source {
+        message.push_str(&format!(": {e}"));
+        source = e.source();
+    }
+
+    message
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+    use crate::data::{tests::init_store, Change};
+
+    /// Never set, for syncs that run to completion.
+    pub(crate) static NO_INTERRUPT: AtomicBool = AtomicBool::new(false);
+
+    /// An empty bare repository in `tmp`, usable as remote.
+    pub(crate) fn bare(tmp: &TempDir) -> Target {
+        let path = tmp.child("remote.git");
+        gix::init_bare(path.path()).unwrap();
+
+        Target {
+            name: "origin".into(),
+            url: path.path().to_string_lossy().into_owned(),
+            token: None,
+        }
+    }
+
+    fn write(path: &str, content: &str) -> Vec<Change> {
+        vec![Change::Write(path.into(), content.into())]
+    }
+
+    fn head(store: &Store) -> gix::ObjectId {
+        gix::open(store.root()).unwrap().head_id().unwrap().detach()
+    }
+
+    #[tokio::test]
+    async fn changes_travel_between_clones() {
+        let tmp = TempDir::new().unwrap();
+        let remote = bare(&tmp);
+        let ours = init_store(&tmp);
+        ours.commit("ours", write("a.toml", "a = 1\n"))
+            .await
+            .unwrap();
+        ours.sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+
+        let other = TempDir::new().unwrap();
+        let theirs = init_store(&other);
+        theirs
+            .sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+        other.child("repo/a.toml").assert("a = 1\n");
+        theirs
+            .commit("theirs", write("b.toml", "b = 1\n"))
+            .await
+            .unwrap();
+        theirs
+            .sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+
+        ours.commit("again", write("c.toml", "c = 1\n"))
+            .await
+            .unwrap();
+        ours.sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+
+        tmp.child("repo/b.toml").assert("b = 1\n");
+        let repo = gix::open(ours.root()).unwrap();
+        let merge = repo.head_commit().unwrap();
+        assert_eq!(2, merge.parent_ids().count());
+        assert!(!repo.is_dirty().unwrap());
+        let branch = repo.head_name().unwrap().unwrap();
+        let pushed = gix::open(&remote.url).unwrap();
+        let mut reference = pushed.find_reference(branch.as_ref()).unwrap();
+        assert_eq!(head(&ours), reference.peel_to_id().unwrap().detach());
+    }
+
+    #[tokio::test]
+    async fn conflicts_are_rolled_back() {
+        let tmp = TempDir::new().unwrap();
+        let remote = bare(&tmp);
+        let ours = init_store(&tmp);
+        ours.commit("base", write("a.toml", "a = 1\n"))
+            .await
+            .unwrap();
+        ours.sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+
+        let other = TempDir::new().unwrap();
+        let theirs = init_store(&other);
+        theirs
+            .sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+        theirs
+            .commit("theirs", write("a.toml", "a = 2\n"))
+            .await
+            .unwrap();
+        theirs
+            .sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+
+        ours.commit("ours", write("a.toml", "a = 3\n"))
+            .await
+            .unwrap();
+        let before = head(&ours);
+        let result = ours
+            .sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::SyncConflict(remote, files)) if remote == "origin" && files == ["a.toml"]
+        ));
+        assert_eq!(before, head(&ours));
+        tmp.child("repo/a.toml").assert("a = 3\n");
+        assert!(!gix::open(ours.root()).unwrap().is_dirty().unwrap());
+    }
+
+    #[tokio::test]
+    async fn interrupted_syncs_stop_before_the_next_step() {
+        let tmp = TempDir::new().unwrap();
+        let remote = bare(&tmp);
+        let store = init_store(&tmp);
+        store
+            .commit("ours", write("a.toml", "a = 1\n"))
+            .await
+            .unwrap();
+        let interrupt = AtomicBool::new(true);
+
+        let result = store.sync(std::slice::from_ref(&remote), &interrupt).await;
+
+        assert!(matches!(result, Err(Error::Interrupted)));
+        let pushed = gix::open(&remote.url).unwrap();
+        assert!(pushed.references().unwrap().all().unwrap().next().is_none());
+    }
+
+    #[test]
+    fn failures_fall_back_to_stdout() {
+        let mut output = std::process::Command::new("git")
+            .arg("--version")
+            .output()
+            .unwrap();
+        output.stdout = b"Automatic merge failed;\nfix conflicts\n".to_vec();
+
+        output.stderr = vec![];
+        assert!(matches!(
+            failed(&["merge"], &output),
+            Error::Git(_, message) if message == "Automatic merge failed; fix conflicts"
+        ));
+
+        output.stderr = b"fatal: refusing\n".to_vec();
+        assert!(matches!(
+            failed(&["merge"], &output),
+            Error::Git(_, message) if message == "fatal: refusing"
+        ));
+    }
+
+    #[tokio::test]
+    async fn unreachable_remotes_fail() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        let missing = Target {
+            name: "origin".into(),
+            url: tmp
+                .child("missing.git")
+                .path()
+                .to_string_lossy()
+                .into_owned(),
+            token: None,
+        };
+
+        assert!(matches!(
+            store.sync(&[missing], &NO_INTERRUPT).await,
+            Err(Error::Git(args, _)) if args.starts_with("fetch")
+        ));
+        assert!(matches!(
+            store.sync(&[], &NO_INTERRUPT).await,
+            Err(Error::NoRemotes)
+        ));
+    }
+
+    #[tokio::test]
+    async fn failures_are_kept_until_a_success() {
+        let tmp = TempDir::new().unwrap();
+        let now = Timestamp::now();
+        let mut state = State::load(tmp.path()).await.unwrap();
+        assert_eq!(State::default(), state);
+
+        state.attempt(now);
+        for _ in 0..=MAX_FAILURES {
+            state.finish(now, &Err(Error::NoRemotes));
+        }
+        state.save(tmp.path()).await.unwrap();
+
+        let mut state = State::load(tmp.path()).await.unwrap();
+        assert_eq!(Some(now), state.attempted);
+        assert_eq!(MAX_FAILURES, state.failures.len());
+        assert_eq!(Error::NoRemotes.to_string(), state.failures[0].error);
+
+        state.finish(now, &Ok::<_, Error>(()));
+        assert!(state.failures.is_empty());
+    }
+}
@@ -0,0 +1,432 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! Undo and redo of commits of the data repository.
+//!
+//! Both create new commits that revert an earlier one, the history is never
+//! rewritten. This keeps commits that have been pushed already intact, an
+//! undo is synced like any other change.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use gix::{bstr::ByteSlice, ObjectId, Repository};
+
+use super::{
+    absence::ABSENCES_DIR,
+    commit_changes,
+    entry::ENTRIES_DIR,
+    history::{blobs, changed_paths, history_error},
+    Change, Error, Result, BUDGETS_FILE,
+};
+
+/// Trailer of undo commits, naming the commit they revert.
+pub const UNDOES: &str = "Undoes";
+
+/// Trailer of redo commits, naming the undo commit they revert.
+pub const REDOES: &str = "Redoes";
+
+/// What can be undone and redone, newest first.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Stacks {
+    undo: Vec<ObjectId>,
+    redo: Vec<ObjectId>,
+}
+
+/// Reverts the last `count` commits that have not been undone yet. Nothing
+/// is committed if any of them conflicts.
+pub(crate) fn undo(root: &Path, count: usize) -> Result<Vec<ObjectId>> {
+    let repo = open(root)?;
+    let stack = stacks(&repo)?.undo;
+    if stack.is_empty() {
+        return Err(Error::NothingToUndo);
+    }
+    ensure_clean(&repo, root)?;
+
+    revert(root, &repo, stack.into_iter().take(count), "undo", UNDOES)
+}
+
+/// Reverts the last `count` undo commits, as long as nothing else has been
+/// committed since. Nothing is committed if any of them conflicts.
+pub(crate) fn redo(root: &Path, count: usize) -> Result<Vec<ObjectId>> {
+    let repo = open(root)?;
+    let stack = stacks(&repo)?.redo;
+    if stack.is_empty() {
+        return Err(Error::NothingToRedo);
+    }
+    ensure_clean(&repo, root)?;
+
+    revert(root, &repo, stack.into_iter().take(count), "redo", REDOES)
+}
+
+pub(super) fn open(root: &Path) -> Result<Repository> {
+    gix::open(root).map_err(|e| Error::GixOpen(root.to_owned(), Box::new(e)))
+}
+
+pub(super) fn ensure_clean(repo: &Repository, root: &Path) -> Result<()> {
+    match repo.is_dirty().map_err(history_error)? {
+        true => Err(Error::Dirty(root.to_owned())),
+        false => Ok(()),
+    }
+}
+
+/// Walks the first parents from `HEAD`. Undo commits take the commit they
+/// revert off the undo stack, redo commits void the undo they revert. Any
+/// other commit clears the redo stack for the undos before it.
+///
+/// Only commits changing records can be undone. Merges are skipped, as
+/// reverting them would drop what was pulled in. The walk ends at the first
+/// commit changing anything else, like the initial layout or a migration,
+/// so the data never goes back to an older format.
+fn stacks(repo: &Repository) -> Result<Stacks> {
+    let mut stacks = Stacks::default();
+    let Some(head) = repo.head_id().ok() else {
+        return Ok(stacks);
+    };
+
+    let mut undone = BTreeSet::new();
+    let mut redone = BTreeSet::new();
+    let mut edited = false;
+
+    for info in head
+        .ancestors()
+        .first_parent_only()
+        .all()
+        .map_err(history_error)?
+    {
+        let info = info.map_err(history_error)?;
+        let commit = info.object().map_err(history_error)?;
+        // the walk only reports first parents, the commit knows all of them
+        if commit.parent_ids().count() > 1 {
+            edited = true;
+            continue;
+        }
+        let paths = changed_paths(repo, info.parent_ids.first().copied(), info.id)?;
+        if !paths.iter().all(|path| is_record(path)) {
+            break;
+        }
+
+        let message = commit.message_raw_sloppy();
+
+        if let Some(undo) = trailer(message, REDOES) {
+            redone.insert(undo);
+        } else if let Some(reverted) = trailer(message, UNDOES) {
+            if !redone.contains(&info.id) {
+                undone.insert(reverted);
+                if !edited {
+                    stacks.redo.push(info.id);
+                }
+            }
+        } else {
+            edited = true;
+            if !undone.contains(&info.id) {
+                stacks.undo.push(info.id);
+            }
+        }
+    }
+
+    Ok(stacks)
+}
+
+fn trailer(message: &gix::bstr::BStr, name: &str) -> Option<ObjectId> {
+    message.lines().find_map(|line| {
+        let value = line.to_str().ok()?.strip_prefix(name)?.strip_prefix(": ")?;
+        ObjectId::from_hex(value.trim().as_bytes()).ok()
+    })
+}
+
+/// Whether `path` belongs to the records, the only files undo and redo
+/// touch.
+fn is_record(path: &Path) -> bool {
+    [ENTRIES_DIR, ABSENCES_DIR, BUDGETS_FILE]
+        .iter()
+        .any(|records| path.starts_with(records))
+}
+
+/// Commits the inverse of each of `commits` on top of `HEAD`, one after the
+/// other. Files that changed again since are not overwritten, every revert
+/// is checked against the ones before it before the first gets committed.
+fn revert<I>(
+    root: &Path,
+    repo: &Repository,
+    commits: I,
+    verb: &str,
+    trailer: &str,
+) -> Result<Vec<ObjectId>>
+where
+    I: IntoIterator<Item = ObjectId>,
+{
+    let mut head = blobs(repo, repo.head_id().ok().map(|id| id.detach()), None)?;
+    let reverts = commits
+        .into_iter()
+        .map(|commit| inverse(repo, commit, &mut head, verb, trailer))
+        .collect::<Result<Vec<_>>>()?;
+
+    reverts
+        .iter()
+        .map(|(message, changes)| commit_changes(root, message, changes))
+        .collect()
+}
+
+/// The message and changes reverting `commit` on top of `head`, which gets
+/// updated to the reverted state.
+fn inverse(
+    repo: &Repository,
+    commit: ObjectId,
+    head: &mut BTreeMap<PathBuf, ObjectId>,
+    verb: &str,
+    trailer: &str,
+) -> Result<(String, Vec<Change>)> {
+    let reverted = repo.find_commit(commit).map_err(history_error)?;
+    let parent = reverted.parent_ids().next().map(|id| id.detach());
+    let summary = reverted
+        .message()
+        .map_err(history_error)?
+        .summary()
+        .to_str_lossy()
+        .into_owned();
+    let summary = summary
+        .strip_prefix("undo ")
+        .map(str::to_owned)
+        .unwrap_or(summary);
+
+    let before = blobs(repo, parent, None)?;
+    let after = blobs(repo, Some(commit), None)?;
+
+    let mut changes = vec![];
+    for path in before.keys().chain(after.keys()).collect::<BTreeSet<_>>() {
+        if before.get(path) == after.get(path) {
+            continue;
+        }
+        if head.get(path) != after.get(path) {
+            return Err(Error::Conflict(commit, path.clone()));
+        }
+
+        changes.push(match before.get(path) {
+            Some(blob) => {
+                let content = repo.find_blob(*blob).map_err(history_error)?;
+                head.insert(path.clone(), *blob);
+                Change::Write(path.clone(), content.data.to_str_lossy().into_owned())
+            }
+            None => {
+                head.remove(path);
+                Change::Remove(path.clone())
+            }
+        });
+    }
+
+    Ok((
+        format!("{verb} {summary}\n\n{trailer}: {commit}\n"),
+        changes,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::{prelude::*, TempDir};
+
+    use super::*;
+    use crate::data::{
+        layout,
+        sync::{
+            tests::{bare, NO_INTERRUPT},
+            Target,
+        },
+        tests::init_store,
+        Entry, Store,
+    };
+
+    async fn record(store: &Store, entry: &Entry) -> ObjectId {
+        store
+            .commit(
+                format!("edit {}", entry.id),
+                vec![Change::record(entry.path(), entry).unwrap()],
+            )
+            .await
+            .unwrap()
+    }
+
+    fn entry(end: Option<&str>) -> Entry {
+        let mut entry = Entry::new(None, vec![], "2026-10-20T08:00:00Z".parse().unwrap());
+        entry.end = end.map(|end| end.parse().unwrap());
+        entry
+    }
+
+    #[tokio::test]
+    async fn undo_and_redo_restore_the_entries() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        let running = entry(None);
+        let stopped = entry(Some("2026-10-20T16:00:00Z"));
+        let typo = entry(Some("2026-10-20T23:00:00Z"));
+        record(&store, &running).await;
+        record(&store, &stopped).await;
+        record(&store, &typo).await;
+
+        store.undo(1).await.unwrap();
+        assert_eq!(vec![stopped.clone()], store.entries().await.unwrap());
+
+        store.undo(2).await.unwrap();
+        assert!(store.entries().await.unwrap().is_empty());
+
+        store.redo(1).await.unwrap();
+        assert_eq!(vec![running], store.entries().await.unwrap());
+
+        store.redo(5).await.unwrap();
+        assert_eq!(vec![typo], store.entries().await.unwrap());
+        assert!(matches!(store.redo(1).await, Err(Error::NothingToRedo)));
+
+        let repo = gix::open(store.root()).unwrap();
+        assert!(!repo.is_dirty().unwrap());
+    }
+
+    #[tokio::test]
+    async fn history_is_never_rewritten() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        let first = record(&store, &entry(None)).await;
+
+        let undo = store.undo(1).await.unwrap();
+        let redo = store.redo(1).await.unwrap();
+
+        let repo = gix::open(store.root()).unwrap();
+        let head = repo.head_id().unwrap().detach();
+        let undo_commit = repo.find_commit(undo[0]).unwrap();
+        assert_eq!(redo[0], head);
+        assert_eq!(vec![first], undo_commit.parent_ids().collect::<Vec<_>>());
+        assert_eq!(
+            Some(first),
+            trailer(undo_commit.message_raw_sloppy(), UNDOES)
+        );
+    }
+
+    #[tokio::test]
+    async fn new_changes_clear_the_redo_stack() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        let other = Entry::new(None, vec![], "2026-10-21T08:00:00Z".parse().unwrap());
+        record(&store, &entry(None)).await;
+
+        store.undo(1).await.unwrap();
+        record(&store, &other).await;
+
+        assert!(matches!(store.redo(1).await, Err(Error::NothingToRedo)));
+        store.undo(1).await.unwrap();
+        assert!(matches!(store.undo(1).await, Err(Error::NothingToUndo)));
+    }
+
+    #[tokio::test]
+    async fn later_changes_are_not_overwritten() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        let running = entry(None);
+        let first = record(&store, &running).await;
+        let stopped = entry(Some("2026-10-20T16:00:00Z"));
+        record(&store, &stopped).await;
+
+        let repo = open(store.root()).unwrap();
+        let result = revert(store.root(), &repo, [first], "undo", UNDOES);
+
+        assert!(matches!(result, Err(Error::Conflict(commit, _)) if commit == first));
+    }
+
+    #[tokio::test]
+    async fn dirty_worktrees_are_refused() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        let running = entry(None);
+        record(&store, &running).await;
+        tmp.child("repo")
+            .child(running.path())
+            .write_str("garbage")
+            .unwrap();
+
+        assert!(matches!(store.undo(1).await, Err(Error::Dirty(_))));
+    }
+
+    #[tokio::test]
+    async fn the_layout_is_never_undone() {
+        let tmp = TempDir::new().unwrap();
+        let store = init_store(&tmp);
+        let layout = layout::seed(store.root()).unwrap();
+        store.commit("initialize", layout).await.unwrap();
+        record(&store, &entry(None)).await;
+
+        assert_eq!(1, store.undo(2).await.unwrap().len());
+        assert!(matches!(store.undo(1).await, Err(Error::NothingToUndo)));
+        assert!(tmp.child("repo").child(layout::README_FILE).path().exists());
+    }
+
+    /// A clone of `ours` sharing its history through `remote`.
+    async fn clone(ours: &Store, remote: &Target, tmp: &TempDir) -> Store {
+        ours.sync(std::slice::from_ref(remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+        let theirs = init_store(tmp);
+        theirs
+            .sync(std::slice::from_ref(remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+        theirs
+    }
+
+    #[tokio::test]
+    async fn merges_are_skipped() {
+        let tmp = TempDir::new().unwrap();
+        let remote = bare(&tmp);
+        let ours = init_store(&tmp);
+        let running = entry(None);
+        record(&ours, &running).await;
+        let other = TempDir::new().unwrap();
+        let theirs = clone(&ours, &remote, &other).await;
+        let pulled = Entry::new(None, vec![], "2026-10-21T08:00:00Z".parse().unwrap());
+        record(&theirs, &pulled).await;
+        theirs
+            .sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+        let stopped = entry(Some("2026-10-20T16:00:00Z"));
+        record(&ours, &stopped).await;
+        ours.sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+
+        assert_eq!(2, ours.undo(2).await.unwrap().len());
+
+        assert_eq!(vec![pulled], ours.entries().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn conflicts_leave_the_repository_untouched() {
+        let tmp = TempDir::new().unwrap();
+        let remote = bare(&tmp);
+        let ours = init_store(&tmp);
+        let running = entry(None);
+        let first = record(&ours, &running).await;
+        let other = TempDir::new().unwrap();
+        let theirs = clone(&ours, &remote, &other).await;
+        let stopped = entry(Some("2026-10-20T16:00:00Z"));
+        record(&theirs, &stopped).await;
+        theirs
+            .sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+        let later = Entry::new(None, vec![], "2026-10-21T08:00:00Z".parse().unwrap());
+        record(&ours, &later).await;
+        ours.sync(std::slice::from_ref(&remote), &NO_INTERRUPT)
+            .await
+            .unwrap();
+        let head = gix::open(ours.root()).unwrap().head_id().unwrap().detach();
+
+        let result = ours.undo(2).await;
+
+        assert!(matches!(result, Err(Error::Conflict(commit, _)) if commit == first));
+        let repo = gix::open(ours.root()).unwrap();
+        assert_eq!(head, repo.head_id().unwrap().detach());
+        assert!(!repo.is_dirty().unwrap());
+        assert_eq!(2, ours.entries().await.unwrap().len());
+    }
+}
@@ -13,3 +13,4 @@ use rstest_reuse;
 
 pub mod cli;
 pub mod config;
+pub mod data;
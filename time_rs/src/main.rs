@@ -9,17 +9,21 @@ use std::{
     collections::HashSet,
     env,
     path::PathBuf,
+    process::{self, Stdio},
     str::FromStr,
     sync::{Arc, LazyLock},
+    time::Duration,
 };
 
 use clap::Parser;
 use color_eyre::eyre::{OptionExt, Result, WrapErr};
 use directories::ProjectDirs;
+use jiff::Timestamp;
 use prodash::{tree::root::Options, tree::Root};
 use time_rs::{
     cli::{commands::Command, Cli, Commands},
-    config::Config,
+    config::{local, Config, Error as ConfigError},
+    data::{format, Error, Lock, Store, SyncState},
 };
 
 const XDG_DATA_HOME: &str = "XDG_DATA_HOME";
@@ -47,30 +51,31 @@ fn get_data_dir() -> Result<PathBuf> {
     Ok(PathBuf::from_str(expanded_str.as_str())?)
 }
 
+/// Config dirs from the least to the most important one: the system dirs from
+/// `XDG_CONFIG_DIRS`, which lists the most important first, then the user dir.
 #[mutants::skip]
 fn get_config_dirs() -> Result<Vec<PathBuf>> {
-    let mut dirs = Vec::new();
-
     let project_dirs = PROJECT_DIRS.as_ref().ok_or_eyre("resolving project dirs")?;
 
     let project_path = project_dirs.project_path();
 
     let mut seen = HashSet::new();
 
-    seen.insert(project_dirs.config_dir().to_owned());
-    dirs.push(project_dirs.config_dir().to_owned());
-
-    env::var("XDG_CONFIG_DIRS")
+    let mut dirs = env::var("XDG_CONFIG_DIRS")
         .into_iter()
-        .for_each(|xdg_dirs| {
+        .flat_map(|xdg_dirs| {
             xdg_dirs
                 .split(':')
                 .map(PathBuf::from)
                 .map(|d| d.join(project_path))
-                .for_each(|p| {
-                    seen.insert(p.to_owned()).then(|| dirs.push(p.to_owned()));
-                })
-        });
+                .collect::<Vec<_>>()
+        })
+        .filter(|p| p != project_dirs.config_dir())
+        .filter(|p| seen.insert(p.to_owned()))
+        .collect::<Vec<_>>();
+    dirs.reverse();
+
+    dirs.push(project_dirs.config_dir().to_owned());
 
     Ok(dirs)
 }
@@ -88,11 +93,85 @@ fn setup_progress() -> Arc<Root> {
     progress
 }
 
+/// Lets long running commands notice Ctrl-C through
+/// `gix::interrupt::IS_INTERRUPTED` and roll back, instead of getting killed
+/// halfway. A second Ctrl-C terminates immediately. Only installed for
+/// commands that check the flag, all others would ignore the first Ctrl-C.
+#[mutants::skip]
+fn setup_interrupt() -> Result<gix::interrupt::Deregister> {
+    // SAFETY: the handler does nothing, so it can't do anything that is
+    // forbidden inside of a signal handler
+    #[allow(unsafe_code)]
+    let deregister = unsafe { gix::interrupt::init_handler(1, || {})? };
+
+    Ok(deregister)
+}
+
+/// Locks the data dir of `config` for the whole run, waiting as configured
+/// unless `--no-wait` is given.
+#[mutants::skip]
+async fn lock_data_dir(cli: &Cli, config: &Config) -> Result<Lock> {
+    let data_dir = config.data_dir.as_ref().ok_or(Error::NoDataDir)?;
+    let timeout = match cli.no_wait {
+        true => Duration::ZERO,
+        false => config.lock.timeout(),
+    };
+
+    Ok(Lock::acquire(data_dir, timeout, &gix::interrupt::IS_INTERRUPTED).await?)
+}
+
+/// Starts `timers repo sync --background` for the same data and config,
+/// detached from the terminal, so it never blocks this process and outlives
+/// it.
+#[mutants::skip]
+fn spawn_sync(cli: &Cli) -> Result<()> {
+    let mut command = process::Command::new(env::current_exe()?);
+    command.args(["repo", "sync", "--background"]);
+    if let Some(ref data_dir) = cli.data_dir {
+        command.arg("--data-dir").arg(data_dir);
+    }
+    if let Some(ref config_dir) = cli.config_dir {
+        command.arg("--config-dir").arg(config_dir);
+    }
+    if let Some(ref profile) = cli.profile {
+        command.arg("--profile").arg(profile);
+    }
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // keeps Ctrl-C in the terminal from reaching the worker
+        command.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        command.creation_flags(DETACHED_PROCESS);
+    }
+
+    command.spawn().wrap_err("starting the background sync")?;
+
+    Ok(())
+}
+
 #[tokio::main]
 #[mutants::skip]
 async fn main() -> Result<()> {
     color_eyre::install()?;
     let cli = Cli::parse();
+    let _interrupt = match cli
+        .command
+        .as_ref()
+        .is_some_and(Commands::handles_interrupts)
+    {
+        true => Some(setup_interrupt()?.auto_deregister()),
+        false => None,
+    };
 
     let progress = setup_progress();
     let handle = prodash::render::line::render(
@@ -102,44 +181,131 @@ async fn main() -> Result<()> {
             .auto_configure(prodash::render::line::StreamKind::Stderr),
     );
 
-    let data_dir = match cli.data_dir {
-        None => get_data_dir()?,
-        Some(ref path) => path.clone(),
-    };
     let config_dir = cli
         .config_dir
         .clone()
         .map_or_else(get_config_dirs, |d| Ok(vec![d]))?;
+    let local_config = local::find(&env::current_dir()?);
+
+    let tolerates_invalid_config = cli
+        .command
+        .as_ref()
+        .is_some_and(Commands::tolerates_invalid_config);
+
+    let unloaded = || Config::unloaded(config_dir.clone(), local_config.clone());
+    let (mut config, loaded) =
+        match Config::load_with_local(config_dir.clone(), local_config.clone()).await {
+            Ok(config) => (config, true),
+            Err(_) if tolerates_invalid_config => (unloaded(), false),
+            Err(e) => return Err(e.into()),
+        };
+    if let Some(ref profile) = cli.profile {
+        match loaded.then(|| config.select_profile(profile)) {
+            Some(Ok(())) => (),
+            Some(Err(e @ ConfigError::UnknownProfile(..))) => return Err(e.into()),
+            // the profile itself may be broken
+            Some(Err(_)) if tolerates_invalid_config => {
+                config = unloaded();
+                config.assume_profile(profile);
+            }
+            Some(Err(e)) => return Err(e.into()),
+            None => config.assume_profile(profile),
+        }
+    }
+    match cli.data_dir {
+        Some(ref path) => config.add_data_dir(path)?,
+        None => config.add_default_data_dir(get_data_dir()?)?,
+    }
+
+    let mut lock = match cli.command.as_ref().is_some_and(Commands::mutates) {
+        true => Some(lock_data_dir(&cli, &config).await?),
+        false => None,
+    };
+
+    if cli.command.as_ref().is_some_and(Commands::uses_data) {
+        let store = Store::open(&config)?;
+        // migrations write, even when the command itself only reads
+        if lock.is_none() && format::read(store.root())?.is_some_and(|f| f < format::VERSION) {
+            lock = Some(lock_data_dir(&cli, &config).await?);
+        }
+        if let Some((from, to)) = store.migrate().await? {
+            eprintln!("migrated the data repository from format {from} to {to}");
+        }
+    }
 
-    let mut config = Config::load(config_dir).await?;
-    config.add_data_dir(data_dir)?;
-
-    use Commands::*;
-
-    let result = match &cli.command {
-        Some(Repo(repo)) => repo
-            .run(progress, &cli, config)
-            .await
-            .wrap_err("repo command"),
-        Some(Start(start)) => start
-            .run(progress, &cli, config)
-            .await
-            .wrap_err("start command"),
-        Some(Status(status)) => status
-            .run(progress, &cli, config)
-            .await
-            .wrap_err("status command"),
-        Some(Stop(stop)) => stop
-            .run(progress, &cli, config)
-            .await
-            .wrap_err("stop command"),
-        Some(Summary(summary)) => summary
-            .run(progress, &cli, config)
-            .await
-            .wrap_err("summary command"),
-        None => todo!("We want to have a dashboard here, later…"),
+    let sync = config.sync.clone();
+    let data_dir = config.data_dir.clone();
+
+    let result = {
+        use Commands::*;
+
+        match &cli.command {
+            Some(Repo(repo)) => repo
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("repo command"),
+            Some(Start(start)) => start
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("start command"),
+            Some(Status(status)) => status
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("status command"),
+            Some(Stop(stop)) => stop
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("stop command"),
+            Some(Summary(summary)) => summary
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("summary command"),
+            Some(Balance(balance)) => balance
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("balance command"),
+            Some(Absence(absence)) => absence
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("absence command"),
+            Some(Check(check)) => check
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("check command"),
+            Some(Budget(budget)) => budget
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("budget command"),
+            Some(History(history)) => history
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("history command"),
+            Some(Undo(undo)) => undo
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("undo command"),
+            Some(Redo(redo)) => redo
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("redo command"),
+            Some(Config(configuration)) => configuration
+                .run(progress, &cli, config)
+                .await
+                .wrap_err("config command"),
+            None => todo!("We want to have a dashboard here, later…"),
+        }
     };
 
+    drop(lock);
+
+    if let (Ok(()), Some(command), Some(data_dir)) = (&result, &cli.command, data_dir) {
+        // a broken state only loses the timing, the worker replaces it
+        let state = SyncState::load(&data_dir).await.unwrap_or_default();
+        if command.syncs_after(&sync, &state, Timestamp::now()) {
+            spawn_sync(&cli)?;
+        }
+    }
+
     handle.shutdown_and_wait();
 
     result
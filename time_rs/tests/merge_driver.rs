@@ -0,0 +1,133 @@
+// SPDX-FileCopyrightText: 2026 Norbert Melzer <timmelzer@gmail.com>
+//
+// SPDX-License-Identifier: MIT
+
+//! git runs the merge driver as `timers`, so it can only be tested against
+//! the built binary.
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use assert_fs::{prelude::*, TempDir};
+use time_rs::data::{Change, Entry, Store};
+
+const TIMERS: &str = env!("CARGO_BIN_EXE_timers");
+
+/// Runs `timers` with `args` in `tmp`, away from the configuration of the
+/// user, with the binary under test first on the `PATH` for git.
+fn timers(tmp: &TempDir, args: &[&str]) {
+    let bin = Path::new(TIMERS).parent().unwrap();
+    let path = env::var_os("PATH").unwrap_or_default();
+    let path = env::join_paths(
+        [bin.as_os_str().to_owned()]
+            .into_iter()
+            .chain(env::split_paths(&path).map(PathBuf::into_os_string)),
+    )
+    .unwrap();
+
+    let output = Command::new(TIMERS)
+        .args(args)
+        .current_dir(tmp.path())
+        .env("PATH", path)
+        .env("HOME", tmp.path())
+        .env("XDG_CONFIG_HOME", tmp.child("config").path())
+        .env("XDG_DATA_HOME", tmp.child("data").path())
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "timers {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn git(args: &[&str]) -> String {
+    let output = Command::new("git").args(args).output().unwrap();
+    assert!(output.status.success());
+
+    String::from_utf8_lossy(&output.stdout).trim().to_owned()
+}
+
+fn data_dir(tmp: &TempDir, name: &str) -> String {
+    tmp.child(name).path().to_string_lossy().into_owned()
+}
+
+async fn record(tmp: &TempDir, name: &str, entry: &Entry) {
+    Store::new(tmp.child(name).child("repo").path())
+        .commit(
+            format!("edit {}", entry.id),
+            vec![Change::record(entry.path(), entry).unwrap()],
+        )
+        .await
+        .unwrap();
+}
+
+async fn entries(tmp: &TempDir, name: &str) -> Vec<Entry> {
+    Store::new(tmp.child(name).child("repo").path())
+        .entries()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn clones_merge_changes_to_different_fields() {
+    let tmp = TempDir::new().unwrap();
+    let remote = tmp.child("remote.git");
+    let url = remote.path().to_str().unwrap();
+    git(&["init", "--quiet", "--bare", url]);
+    // git merges changes to lines that aren't next to each other by itself
+    let running = Entry {
+        client: Some("acme".to_owned()),
+        ..Entry::new(
+            Some("timers".to_owned()),
+            vec![],
+            "2026-10-20T08:00:00Z".parse().unwrap(),
+        )
+    };
+
+    let seed = data_dir(&tmp, "seed");
+    timers(&tmp, &["-d", &seed, "repo", "init", "--remote", url]);
+    record(&tmp, "seed", &running).await;
+    timers(&tmp, &["-d", &seed, "repo", "sync"]);
+    let repo = tmp.child("seed").child("repo");
+    let branch = git(&[
+        "-C",
+        repo.path().to_str().unwrap(),
+        "symbolic-ref",
+        "--short",
+        "HEAD",
+    ]);
+
+    for clone in ["ours", "theirs"] {
+        let data = data_dir(&tmp, clone);
+        timers(
+            &tmp,
+            &["-d", &data, "repo", "clone", url, "--branch", &branch],
+        );
+    }
+
+    let moved = Entry {
+        project: Some("time_rs".to_owned()),
+        ..running.clone()
+    };
+    let billed = Entry {
+        client: Some("initech".to_owned()),
+        ..running.clone()
+    };
+    record(&tmp, "ours", &moved).await;
+    record(&tmp, "theirs", &billed).await;
+    for clone in ["ours", "theirs", "ours"] {
+        timers(&tmp, &["-d", &data_dir(&tmp, clone), "repo", "sync"]);
+    }
+
+    let merged = Entry {
+        client: Some("initech".to_owned()),
+        ..moved
+    };
+    assert_eq!(vec![merged.clone()], entries(&tmp, "ours").await);
+    assert_eq!(vec![merged], entries(&tmp, "theirs").await);
+}